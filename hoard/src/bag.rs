@@ -11,7 +11,7 @@ use crate::pointee::Pointee;
 use crate::blob::*;
 use crate::load::{Load, LoadRef, MaybeValid};
 use crate::owned::{Ref, Take, IntoOwned, RefOwn};
-use crate::ptr::{Ptr, PtrClean, PtrBlob, Get, TryGet, GetMut, TryGetMut, AsZone};
+use crate::ptr::{Alloc, Ptr, PtrClean, PtrBlob, Get, TryGet, GetMut, TryGetMut, AsZone};
 use crate::save::{Save, SavePoll, SaveRef, SaveRefPoll, Saver};
 
 #[repr(C)]
@@ -30,6 +30,13 @@ impl<T: ?Sized + Pointee, P: Ptr> Drop for Bag<T, P> {
 }
 
 impl<T: ?Sized + Pointee, P: Ptr> Bag<T, P> {
+    /// Creates a new `Bag`, allocated by `alloc`.
+    pub fn new_in<A>(src: impl Take<T>, alloc: &mut A) -> Self
+        where A: Alloc<Ptr = P>
+    {
+        alloc.alloc(src)
+    }
+
     pub unsafe fn from_raw_parts(ptr: P, metadata: T::Metadata) -> Self {
         Self {
             marker: PhantomData,
@@ -91,6 +98,23 @@ where T: LoadRef,
         }.trust()
     }
 
+    /// Gets multiple bags at once.
+    ///
+    /// The results are returned in the same order as `bags`.
+    #[track_caller]
+    pub fn get_many<'a>(bags: &[&'a Self]) -> Vec<Ref<'a, T>>
+        where P: Get
+    {
+        let ptrs: Vec<(&'a P, T::Metadata)> = bags.iter()
+                                                  .map(|bag| (&bag.ptr, bag.metadata()))
+                                                  .collect();
+        unsafe {
+            P::get_many::<T>(&ptrs)
+        }.into_iter()
+         .map(MaybeValid::trust)
+         .collect()
+    }
+
     pub fn try_get<'a>(&'a self) -> Result<Ref<'a, T>, P::Error>
         where P: TryGet
     {
@@ -129,6 +153,28 @@ where T: fmt::Debug, P: fmt::Debug,
     }
 }
 
+impl<T: ?Sized + Pointee, P: Ptr> Bag<T, P> {
+    /// Returns a `Debug` implementation that prints the pointer and metadata, but never the value.
+    pub fn debug_shallow(&self) -> impl fmt::Debug + '_
+        where P::Clean: fmt::Debug
+    {
+        struct DebugShallow<'a, T: ?Sized + Pointee, P: Ptr>(&'a Bag<T, P>);
+
+        impl<T: ?Sized + Pointee, P: Ptr> fmt::Debug for DebugShallow<'_, T, P>
+        where P::Clean: fmt::Debug
+        {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_struct(any::type_name::<Bag<T, P>>())
+                    .field("ptr", &self.0.try_get_dirty().map(|_| "dirty"))
+                    .field("metadata", &self.0.metadata)
+                    .finish()
+            }
+        }
+
+        DebugShallow(self)
+    }
+}
+
 #[derive(Error)]
 #[error("FIXME")]
 pub enum DecodeBagBytesError<T: ?Sized + BlobDyn, P: PtrBlob> {
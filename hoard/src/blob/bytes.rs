@@ -1,15 +1,20 @@
 //! Byte sizes for blobs.
 
+use std::any::TypeId;
 use std::convert::TryFrom;
 use std::fmt;
 use std::marker::PhantomData;
-use std::mem::MaybeUninit;
+use std::mem::{self, MaybeUninit};
 use std::ops::{Deref, DerefMut};
+use std::ptr;
 use std::slice;
 
 use crate::pointee::Pointee;
 
-use super::{Blob, BlobDyn, MaybeValid};
+use super::{Blob, BlobDyn, MaybeValid, NonZeroPaddingError};
+
+/// An untyped view of some bytes, as returned by `Bytes::split_at`.
+pub type RawBytes<'a> = Bytes<'a, [u8]>;
 
 /// A byte slice whose length is guaranteed to be the correct size for a blob.
 pub struct Bytes<'a, T: ?Sized + BlobDyn> {
@@ -142,6 +147,21 @@ where T: BlobDyn + Pointee
     pub fn assume_valid(self) -> ValidBytes<'a, T> {
         ValidBytes(self)
     }
+
+    /// Splits the bytes into two raw byte slices at `mid`.
+    ///
+    /// Returns `None` if `mid` is out of bounds.
+    pub fn split_at(self, mid: usize) -> Option<(RawBytes<'a>, RawBytes<'a>)> {
+        let bytes: &'a [u8] = unsafe { slice::from_raw_parts(self.ptr.cast(), self.len()) };
+
+        if mid <= bytes.len() {
+            let (left, right) = bytes.split_at(mid);
+            Some((Bytes::try_from_slice(left, left.len()).ok().unwrap(),
+                  Bytes::try_from_slice(right, right.len()).ok().unwrap()))
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, T: ?Sized + BlobDyn> StructCursor<'a, T> {
@@ -159,6 +179,54 @@ impl<'a, T: ?Sized + BlobDyn> StructCursor<'a, T> {
             .map(|maybe| maybe.trust())
     }
 
+    /// Decodes `N` consecutive fields of the same type as an array.
+    ///
+    /// Decoding stops at the first element that fails, and `f` is called with the index of that
+    /// element and its error.
+    pub fn decode_array<F: Blob, E, const N: usize>(
+        &mut self,
+        f: impl FnOnce(usize, F::DecodeBytesError) -> E
+    ) -> Result<MaybeValid<[F; N]>, E>
+    {
+        let mut r: [MaybeUninit<F>; N] = unsafe { MaybeUninit::uninit().assume_init() };
+
+        for idx in 0 .. N {
+            match self.trust_field::<F>() {
+                Ok(item) => r[idx] = MaybeUninit::new(item),
+                Err(err) => {
+                    for initialized in &mut r[.. idx] {
+                        unsafe { ptr::drop_in_place(initialized.as_mut_ptr()) }
+                    }
+                    return Err(f(idx, err));
+                }
+            }
+        }
+
+        Ok(MaybeValid::from(unsafe { mem::transmute_copy::<_, [F; N]>(&r) }))
+    }
+
+    /// Checks the padding of the next field, without decoding it.
+    pub fn validate_field_padding<F: Blob>(&mut self) -> Result<(), NonZeroPaddingError> {
+        let field_bytes = self.bytes.get(self.idx .. self.idx + F::SIZE)
+                                        .expect("overflow");
+        let field_bytes = Bytes::<F>::try_from(field_bytes).unwrap();
+        F::validate_padding(field_bytes)?;
+        self.idx += F::SIZE;
+        Ok(())
+    }
+
+    /// Checks that the next `len` bytes, written by `write_padding`, are all zeros.
+    pub fn validate_padding(&mut self, len: usize) -> Result<(), NonZeroPaddingError> {
+        let padding = self.bytes.get(self.idx .. self.idx + len)
+                                    .expect("overflow");
+        self.idx += len;
+        if padding.iter().all(|b| *b == 0) {
+            Ok(())
+        } else {
+            Err(NonZeroPaddingError)
+        }
+    }
+
     #[track_caller]
     pub fn assert_done(self) -> Bytes<'a, T> {
         assert_eq!(self.idx, self.bytes.len(), "not all bytes used");
@@ -276,15 +344,47 @@ impl<'a, T: ?Sized + BlobDyn> WriteStruct<'a, T> {
         self
     }
 
+    /// Writes every element of a slice contiguously.
+    ///
+    /// Equivalent to calling `write_field` on each element, but with a single bounds check, and a
+    /// single copy for byte slices.
+    #[track_caller]
+    pub fn write_slice<F: Blob>(mut self, slice: &[F]) -> Self {
+        let len = F::SIZE.checked_mul(slice.len()).expect("overflow");
+        let dst = self.bytes.get_mut(self.written .. self.written + len).expect("overflow");
+
+        if TypeId::of::<F>() == TypeId::of::<u8>() {
+            unsafe {
+                ptr::copy_nonoverlapping(slice.as_ptr().cast::<u8>(), dst.as_mut_ptr().cast::<u8>(), len);
+            }
+        } else {
+            for (idx, item) in slice.iter().enumerate() {
+                let item_dst = &mut dst[idx * F::SIZE .. (idx + 1) * F::SIZE];
+                let item_dst = BytesUninit::<F>::try_from(item_dst).unwrap();
+                item.encode_bytes(item_dst);
+            }
+        }
+
+        self.written += len;
+        self
+    }
+
+    /// Writes `len` zero bytes in a single operation.
     #[track_caller]
-    pub fn write_padding(mut self, len: usize) -> Self {
-        for b in self.bytes.get_mut(self.written .. self.written + len).expect("overflow") {
-            *b = MaybeUninit::new(0);
+    pub fn write_zeros(mut self, len: usize) -> Self {
+        let dst = self.bytes.get_mut(self.written .. self.written + len).expect("overflow");
+        unsafe {
+            ptr::write_bytes(dst.as_mut_ptr(), 0, dst.len());
         }
         self.written += len;
         self
     }
 
+    #[track_caller]
+    pub fn write_padding(self, len: usize) -> Self {
+        self.write_zeros(len)
+    }
+
     #[track_caller]
     pub fn done(self) -> Bytes<'a, T> {
         assert_eq!(self.bytes.len(), self.written, "not all bytes written");
@@ -295,3 +395,71 @@ impl<'a, T: ?Sized + BlobDyn> WriteStruct<'a, T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_padding() {
+        let mut buf = [0xff; 66];
+        let dst = BytesUninit::<[u8; 66]>::try_from(&mut buf[..]).unwrap();
+        let dst = dst.write_struct()
+                     .write_field(&1u8)
+                     .write_padding(64)
+                     .write_field(&2u8)
+                     .done();
+
+        assert_eq!(dst[0], 1);
+        assert!(dst[1 .. 65].iter().all(|b| *b == 0));
+        assert_eq!(dst[65], 2);
+    }
+
+    #[test]
+    fn split_at() {
+        let buf = [0x78, 0x56, 0x34, 0x12, 1, 2, 3, 4, 5, 6];
+        let bytes = Bytes::<[u8; 10]>::try_from(&buf[..]).unwrap();
+
+        let (left, right) = bytes.split_at(4).unwrap();
+        assert_eq!(&*left, &[0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(right.metadata(), 6);
+        assert_eq!(&*right, &[1, 2, 3, 4, 5, 6]);
+
+        let left = Bytes::<u32>::try_from(&*left).unwrap();
+        assert_eq!(<u32 as Blob>::decode_bytes(left).unwrap().trust(), 0x1234_5678);
+
+        let (left, right) = bytes.split_at(10).unwrap();
+        assert_eq!(left.len(), 10);
+        assert_eq!(right.len(), 0);
+
+        assert!(bytes.split_at(11).is_none());
+    }
+
+    #[test]
+    fn write_slice() {
+        let txid = [0xabu8; 32];
+
+        let mut expected = [0xff; 33];
+        let dst = BytesUninit::<(u8, [u8; 32])>::try_from(&mut expected[..]).unwrap();
+        let mut dst = dst.write_struct().write_field(&1u8);
+        for b in txid.iter() {
+            dst = dst.write_field(b);
+        }
+        dst.done();
+
+        let mut actual = [0xff; 33];
+        let dst = BytesUninit::<(u8, [u8; 32])>::try_from(&mut actual[..]).unwrap();
+        dst.write_struct()
+           .write_field(&1u8)
+           .write_slice(&txid)
+           .done();
+        assert_eq!(actual, expected);
+
+        let mut buf = [0xff; 4];
+        let dst = BytesUninit::<[u16; 2]>::try_from(&mut buf[..]).unwrap();
+        dst.write_struct()
+           .write_slice(&[0x1234u16, 0x5678])
+           .done();
+        assert_eq!(buf, [0x34, 0x12, 0x78, 0x56]);
+    }
+}
@@ -0,0 +1,112 @@
+//! CRC32 checksum trailers for saved data.
+//!
+//! Checksums are opt-in: wrap the destination of an `IoSaver` in a `ChecksummedWriter`, and load
+//! the result through a `Checksummed` map, which verifies the trailer before anything is decoded.
+
+use std::io;
+
+use thiserror::Error;
+
+/// Writer that appends a CRC32 of everything written to it when finished.
+#[derive(Debug)]
+pub struct ChecksummedWriter<W> {
+    inner: W,
+    hasher: crc32fast::Hasher,
+}
+
+impl<W: io::Write> ChecksummedWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: crc32fast::Hasher::new(),
+        }
+    }
+
+    /// Writes the checksum trailer, returning the inner writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let crc = self.hasher.finalize();
+        self.inner.write_all(&crc.to_le_bytes())?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: io::Write> io::Write for ChecksummedWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[.. n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("FIXME")]
+pub enum ChecksumError {
+    Truncated,
+    Mismatch {
+        expected: u32,
+        actual: u32,
+    },
+}
+
+/// Verifies the checksum trailer written by `ChecksummedWriter`, returning the data without it.
+pub fn verify_checksum(buf: &[u8]) -> Result<&[u8], ChecksumError> {
+    if buf.len() < 4 {
+        return Err(ChecksumError::Truncated);
+    }
+    let (data, trailer) = buf.split_at(buf.len() - 4);
+
+    let mut expected = [0; 4];
+    expected.copy_from_slice(trailer);
+    let expected = u32::from_le_bytes(expected);
+
+    let mut hasher = crc32fast::Hasher::new();
+    hasher.update(data);
+    let actual = hasher.finalize();
+    if actual == expected {
+        Ok(data)
+    } else {
+        Err(ChecksumError::Mismatch { expected, actual })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bag::Bag;
+    use crate::ptr::{Ptr, Heap};
+    use crate::ptr::key::Key;
+    use crate::ptr::key::map::Checksummed;
+    use crate::ptr::key::offset::{IoSaver, OffsetSaver};
+
+    #[test]
+    fn checksummed_save() {
+        let map: &[u8] = &[];
+        let bag = Heap::alloc(42u8);
+
+        let (expected_offset, expected_buf) = OffsetSaver::new(map).try_save(&bag).unwrap();
+
+        let saver = IoSaver::new(map, ChecksummedWriter::new(vec![]));
+        let (offset, dst) = saver.try_save(&bag).unwrap();
+        let buf = dst.finish().unwrap();
+        assert_eq!(offset, expected_offset);
+        assert_eq!(buf.len(), expected_buf.len() + 4);
+
+        let map = Checksummed::verify(&buf).unwrap();
+        assert_eq!(map.as_ref(), &expected_buf[..]);
+
+        let key = Key::from_offset(offset, map);
+        let bag: Bag<Bag<u8, Key<Checksummed>>, _> = unsafe { Bag::from_raw_parts(key, ()) };
+        assert_eq!(*bag.get().get(), 42);
+
+        let mut corrupt = buf.clone();
+        corrupt[0] ^= 1;
+        assert!(matches!(Checksummed::verify(&corrupt), Err(ChecksumError::Mismatch { .. })));
+
+        assert_eq!(Checksummed::verify(&buf[.. 3]).unwrap_err(), ChecksumError::Truncated);
+    }
+}
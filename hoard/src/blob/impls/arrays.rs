@@ -1,7 +1,8 @@
 use super::*;
 
-use std::any::type_name;
+use std::any::{type_name, TypeId};
 use std::fmt;
+use std::ptr;
 
 #[derive(Error, Debug)]
 #[error("FIXME")]
@@ -30,18 +31,32 @@ impl<T: Blob, const N: usize> Blob for [T; N] {
     type DecodeBytesError = DecodeArrayBytesError<T::DecodeBytesError, N>;
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
-        let mut dst = dst.write_struct();
+        dst.write_struct()
+           .write_slice(&self[..])
+           .done()
+    }
 
-        for item in self.iter() {
-            dst = dst.write_field(item);
+    fn decode_bytes(src: Bytes<'_, Self>) -> Result<MaybeValid<Self>, Self::DecodeBytesError> {
+        if TypeId::of::<T>() == TypeId::of::<u8>() {
+            // Every byte is a valid u8, so the whole array can be copied without per-element
+            // decoding.
+            let r = unsafe { ptr::read_unaligned(src.as_ptr() as *const Self) };
+            return Ok(MaybeValid::from(r));
         }
 
-        dst.done()
+        let mut fields = src.struct_fields();
+        let r = fields.decode_array(|idx, err| DecodeArrayBytesError { idx, err })?;
+        fields.assert_done();
+        Ok(r)
     }
 
-    fn decode_bytes(src: Bytes<'_, Self>) -> Result<MaybeValid<Self>, Self::DecodeBytesError> {
-        let _ = src;
-        todo!()
+    fn validate_padding(src: Bytes<'_, Self>) -> Result<(), NonZeroPaddingError> {
+        let mut fields = src.struct_fields();
+        for _ in 0 .. N {
+            fields.validate_field_padding::<T>()?;
+        }
+        fields.assert_done();
+        Ok(())
     }
 }
 
@@ -100,7 +115,41 @@ impl<'a, T: Validate<'a>, const N: usize> ValidatePoll<T::Ptr> for ValidateArray
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    use crate::primitive::impls::DecodeBoolError;
+
     #[test]
     fn encode() {
     }
+
+    #[test]
+    fn decode_bytes() {
+        let buf = Bytes::<[u8; 4]>::try_from(&[1, 2, 3, 4][..]).unwrap();
+        assert_eq!(<[u8; 4] as Blob>::decode_bytes(buf).unwrap().trust(),
+                   [1, 2, 3, 4]);
+
+        let buf = Bytes::<[bool; 4]>::try_from(&[1, 0, 1, 0][..]).unwrap();
+        assert_eq!(<[bool; 4] as Blob>::decode_bytes(buf).unwrap().trust(),
+                   [true, false, true, false]);
+
+        let buf = Bytes::<[bool; 4]>::try_from(&[1, 0, 2, 0][..]).unwrap();
+        let err = <[bool; 4] as Blob>::decode_bytes(buf).unwrap_err();
+        assert_eq!(err.idx, 2);
+        assert_eq!(err.err, DecodeBoolError);
+    }
+
+    #[test]
+    fn decode_bytes_u8_fast_path() {
+        let buf: Vec<u8> = (0 .. 32).map(|i| (i * 37 + 11) as u8).collect();
+        let src = Bytes::<[u8; 32]>::try_from(&buf[..]).unwrap();
+
+        let fast = <[u8; 32] as Blob>::decode_bytes(src).unwrap().trust();
+        assert_eq!(&fast[..], &buf[..]);
+
+        let slow: [u8; 32] = src.struct_fields()
+                                .decode_array(|_idx, err: !| err)
+                                .unwrap().trust();
+        assert_eq!(fast, slow);
+    }
 }
@@ -9,35 +9,71 @@ pub enum DecodeOptionBytesError<E: std::fmt::Debug> {
 }
 
 impl<T: Blob> Blob for Option<T> {
-    const SIZE: usize = 1 + T::SIZE;
+    const SIZE: usize = if T::NONZERO_NICHE { T::SIZE } else { 1 + T::SIZE };
 
     type DecodeBytesError = DecodeOptionBytesError<T::DecodeBytesError>;
 
     fn decode_bytes(src: Bytes<'_, Self>) -> Result<MaybeValid<Self>, Self::DecodeBytesError> {
-        let mut fields = src.struct_fields();
-
-        match fields.trust_field::<u8>().into_ok() {
-            0 => {
-                // FIXME: check padding
+        if T::NONZERO_NICHE {
+            if src.iter().all(|b| *b == 0) {
                 Ok(MaybeValid::from(None))
-            },
-            1 => {
+            } else {
+                let mut fields = src.struct_fields();
                 let inner = fields.trust_field::<T>().map_err(DecodeOptionBytesError::Value)?;
+                fields.assert_done();
                 Ok(MaybeValid::from(Some(inner)))
-            },
-            _ => Err(DecodeOptionBytesError::Discriminant),
+            }
+        } else {
+            let mut fields = src.struct_fields();
+
+            match fields.trust_field::<u8>().into_ok() {
+                0 => Ok(MaybeValid::from(None)),
+                1 => {
+                    let inner = fields.trust_field::<T>().map_err(DecodeOptionBytesError::Value)?;
+                    Ok(MaybeValid::from(Some(inner)))
+                },
+                _ => Err(DecodeOptionBytesError::Discriminant),
+            }
         }
     }
 
+    fn validate_padding(src: Bytes<'_, Self>) -> Result<(), NonZeroPaddingError> {
+        let mut fields = src.struct_fields();
+        if T::NONZERO_NICHE {
+            if src.iter().all(|b| *b == 0) {
+                fields.validate_padding(T::SIZE)?;
+            } else {
+                fields.validate_field_padding::<T>()?;
+            }
+        } else {
+            match fields.trust_field::<u8>().into_ok() {
+                0 => fields.validate_padding(T::SIZE)?,
+                1 => fields.validate_field_padding::<T>()?,
+                // An invalid discriminant is left for decode_bytes to reject.
+                _ => return Ok(()),
+            }
+        }
+        fields.assert_done();
+        Ok(())
+    }
+
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
         let dst = dst.write_struct();
-        match self {
-            None => {
+        match (self, T::NONZERO_NICHE) {
+            (None, true) => {
+                dst.write_padding(T::SIZE)
+                   .done()
+            },
+            (Some(inner), true) => {
+                dst.write_field(inner)
+                   .done()
+            },
+            (None, false) => {
                 dst.write_field(&0u8)
                    .write_padding(T::SIZE)
                    .done()
             },
-            Some(inner) => {
+            (Some(inner), false) => {
                 dst.write_field(&1u8)
                    .write_field(inner)
                    .done()
@@ -55,4 +91,45 @@ mod tests {
         let opt: Option<u8> = Some(23);
         assert_eq!(opt.to_blob_bytes(), &[1,23]);
     }
+
+    #[test]
+    fn nonzero_niche() {
+        use std::num::NonZeroU32;
+
+        let some: Option<u32> = Some(7);
+        assert_eq!(some.to_blob_bytes(), &[1, 7, 0, 0, 0]);
+        assert_eq!(None::<u32>.to_blob_bytes(), &[0, 0, 0, 0, 0]);
+
+        assert_eq!(<Option<NonZeroU32> as Blob>::SIZE, 4);
+        let some = NonZeroU32::new(7);
+        assert_eq!(some.to_blob_bytes(), &[7, 0, 0, 0]);
+        assert_eq!(None::<NonZeroU32>.to_blob_bytes(), &[0, 0, 0, 0]);
+
+        let src = Bytes::<Option<NonZeroU32>>::try_from(&[7, 0, 0, 0][..]).unwrap();
+        assert_eq!(<Option<NonZeroU32> as Blob>::decode_bytes(src).unwrap().trust(), some);
+        let src = Bytes::<Option<NonZeroU32>>::try_from(&[0, 0, 0, 0][..]).unwrap();
+        assert_eq!(<Option<NonZeroU32> as Blob>::decode_bytes(src).unwrap().trust(), None);
+    }
+
+    #[test]
+    fn decode_bytes_strict() {
+        use std::num::NonZeroU32;
+
+        let src = Bytes::<Option<u32>>::try_from(&[0, 0, 0, 0, 0][..]).unwrap();
+        assert_eq!(<Option<u32> as Blob>::decode_bytes_strict(src).unwrap().trust(), None);
+
+        let src = Bytes::<Option<u32>>::try_from(&[0, 0, 1, 0, 0][..]).unwrap();
+        assert_eq!(<Option<u32> as Blob>::decode_bytes(src).unwrap().trust(), None);
+        assert!(matches!(<Option<u32> as Blob>::decode_bytes_strict(src),
+                         Err(DecodeStrictError::NonZeroPadding)));
+
+        let src = Bytes::<Option<NonZeroU32>>::try_from(&[0, 0, 0, 0][..]).unwrap();
+        assert_eq!(<Option<NonZeroU32> as Blob>::decode_bytes_strict(src).unwrap().trust(), None);
+
+        // Padding nested inside the value is checked too.
+        let src = Bytes::<Option<Option<u8>>>::try_from(&[1, 0, 1][..]).unwrap();
+        assert_eq!(<Option<Option<u8>> as Blob>::decode_bytes(src).unwrap().trust(), Some(None));
+        assert!(matches!(<Option<Option<u8>> as Blob>::decode_bytes_strict(src),
+                         Err(DecodeStrictError::NonZeroPadding)));
+    }
 }
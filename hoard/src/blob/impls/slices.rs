@@ -16,8 +16,7 @@ unsafe impl<T: Blob> BlobDyn for [T] {
 
     fn try_size(len: usize) -> Result<usize, Self::LayoutError> {
         T::SIZE.checked_mul(len).and_then(|size|
-            // FIXME: is this limit really reasonable?
-            if size <= isize::MAX as usize {
+            if size <= Self::MAX_SIZE {
                 Some(size)
             } else {
                 None
@@ -26,11 +25,9 @@ unsafe impl<T: Blob> BlobDyn for [T] {
     }
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
-        let mut dst = dst.write_struct();
-        for item in self {
-            dst = dst.write_field(item);
-        }
-        dst.done()
+        dst.write_struct()
+           .write_slice(self)
+           .done()
     }
 
     fn decode_bytes(blob: Bytes<'_, Self>) -> Result<MaybeValid<Self::Owned>, Self::DecodeBytesError> {
@@ -54,6 +51,7 @@ mod test {
     use super::*;
 
     use crate::prelude::*;
+    use crate::blob::bytes::TryFromSliceError;
 
     #[test]
     fn try_size() {
@@ -67,6 +65,20 @@ mod test {
         assert_eq!(<[u8] as BlobDyn>::try_size(usize::MAX), Err(SliceLayoutError));
     }
 
+    #[test]
+    fn oversized_metadata() {
+        assert_eq!(<[u16] as BlobDyn>::MAX_SIZE, isize::MAX as usize);
+
+        assert_eq!(<[u16] as BlobDyn>::try_size(isize::MAX as usize / 2), Ok(isize::MAX as usize - 1));
+        assert_eq!(<[u16] as BlobDyn>::try_size(isize::MAX as usize / 2 + 1), Err(SliceLayoutError));
+
+        // Crafted metadata is rejected before the bytes are even looked at.
+        match Bytes::<[u16]>::try_from_slice(&[], usize::MAX / 2) {
+            Err(TryFromSliceError::Metadata(SliceLayoutError)) => {},
+            r => panic!("unexpected {:?}", r),
+        }
+    }
+
     #[test]
     fn encode_decode_roundtrip() {
         #[track_caller]
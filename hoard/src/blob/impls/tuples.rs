@@ -71,6 +71,17 @@ macro_rules! impl_tuple {
 
                 Ok(MaybeValid::from(( $($name,)+ )))
             }
+
+            fn validate_padding(blob: Bytes<'_, Self>) -> Result<(), NonZeroPaddingError> {
+                let mut fields = blob.struct_fields();
+
+                $(
+                    fields.validate_field_padding::<$name>()?;
+                )+
+
+                fields.assert_done();
+                Ok(())
+            }
         }
     }
 }
@@ -5,10 +5,18 @@
 //! with structs simply concatenating together every field.
 
 use std::convert::TryFrom;
+use std::error;
 use std::marker::PhantomData;
 
+use thiserror::Error;
+
 pub mod bytes;
-pub use self::bytes::{Bytes, BytesUninit, ValidBytes};
+pub use self::bytes::{Bytes, BytesUninit, RawBytes, ValidBytes};
+
+pub mod reader;
+pub use self::reader::PileReader;
+
+pub mod checksum;
 
 pub mod impls;
 
@@ -20,11 +28,36 @@ pub use crate::validate::MaybeValid;
 /// A sized type with a fixed-size binary serialization.
 pub trait Blob : 'static + Sized {
     const SIZE: usize;
+
+    /// Whether an all-zero encoding is never valid.
+    ///
+    /// If so, `Option<Self>` uses all-zeros for `None`, rather than a discriminant byte.
+    const NONZERO_NICHE: bool = false;
+
     type DecodeBytesError : 'static + std::error::Error + Send;
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self>;
     fn decode_bytes(blob: Bytes<'_, Self>) -> Result<MaybeValid<Self>, Self::DecodeBytesError>;
 
+    /// Checks that any padding in `blob` is all zeros.
+    ///
+    /// `decode_bytes` ignores padding, so a value with padding has more than one encoding. Types
+    /// with padding, or with fields that may have padding, override this; the default assumes
+    /// there is none.
+    fn validate_padding(blob: Bytes<'_, Self>) -> Result<(), NonZeroPaddingError> {
+        let _ = blob;
+        Ok(())
+    }
+
+    /// Like `decode_bytes`, but also rejects non-zero padding.
+    ///
+    /// This is opt-in: use it when the canonical encoding matters, eg. when a digest covers the
+    /// serialized bytes.
+    fn decode_bytes_strict(blob: Bytes<'_, Self>) -> Result<MaybeValid<Self>, DecodeStrictError<Self::DecodeBytesError>> {
+        Self::validate_padding(blob).map_err(|NonZeroPaddingError| DecodeStrictError::NonZeroPadding)?;
+        Self::decode_bytes(blob).map_err(DecodeStrictError::Decode)
+    }
+
     /// Convenience wrapper around `encode_bytes`.
     ///
     /// # Examples
@@ -45,10 +78,28 @@ pub trait Blob : 'static + Sized {
     }
 }
 
+/// Returned by `Blob::validate_padding` when a padding byte is non-zero.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("FIXME")]
+pub struct NonZeroPaddingError;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("FIXME")]
+pub enum DecodeStrictError<E: error::Error> {
+    NonZeroPadding,
+    Decode(E),
+}
+
 /// A type where the size of the binary serialization is determined by pointer metadata.
 ///
 /// This trait has a blanket implementation for all `T: Blob`.
 pub unsafe trait BlobDyn : 'static + Pointee + IntoOwned {
+    /// The maximum size of the binary serialization.
+    ///
+    /// `try_size` must return an error for any metadata that would result in a larger size, so
+    /// that untrusted metadata can't lead to huge allocations.
+    const MAX_SIZE: usize = isize::MAX as usize;
+
     type DecodeBytesError : 'static + std::error::Error + Send;
 
     fn try_size(metadata: Self::Metadata) -> Result<usize, Self::LayoutError>;
@@ -0,0 +1,82 @@
+//! Sequential decoding of packed blobs.
+
+use std::convert::TryFrom;
+
+use thiserror::Error;
+
+use crate::primitive::Primitive;
+
+use super::{Blob, Bytes, MaybeValid};
+
+/// A read cursor over a byte slice of packed blobs.
+///
+/// The read-side counterpart of `WriteStruct`: each read decodes the next blob and advances the
+/// cursor past it.
+#[derive(Debug, Clone)]
+pub struct PileReader<'a> {
+    buf: &'a [u8],
+    idx: usize,
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("FIXME")]
+pub enum ReadBlobError<E: std::error::Error> {
+    Truncated {
+        expected: usize,
+        remaining: usize,
+    },
+    Decode(E),
+}
+
+impl<'a> PileReader<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { buf, idx: 0 }
+    }
+
+    /// The bytes that haven't been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.buf[self.idx ..]
+    }
+
+    pub fn read_blob<T: Blob>(&mut self) -> Result<MaybeValid<T>, ReadBlobError<T::DecodeBytesError>> {
+        let remaining = self.remaining();
+        let blob_bytes = remaining.get(.. T::SIZE)
+                                  .ok_or(ReadBlobError::Truncated { expected: T::SIZE, remaining: remaining.len() })?;
+
+        let blob_bytes = Bytes::<T>::try_from(blob_bytes).unwrap();
+        let blob = T::decode_bytes(blob_bytes).map_err(ReadBlobError::Decode)?;
+        self.idx += T::SIZE;
+        Ok(blob)
+    }
+
+    pub fn read_primitive<T: Primitive>(&mut self) -> Result<T, ReadBlobError<T::DecodeBytesError>> {
+        self.read_blob::<T>()
+            .map(|maybe| maybe.trust())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::primitive::impls::DecodeBoolError;
+
+    #[test]
+    fn read_primitive() {
+        let buf = [42, 0x78, 0x56, 0x34, 0x12, 1, 2, 3, 4, 0xff, 0xee];
+        let mut reader = PileReader::new(&buf);
+
+        assert_eq!(reader.read_primitive::<u8>(), Ok(42));
+        assert_eq!(reader.read_primitive::<u32>(), Ok(0x1234_5678));
+        assert_eq!(reader.read_blob::<[u8; 4]>().unwrap().trust(), [1, 2, 3, 4]);
+        assert_eq!(reader.remaining(), &[0xff, 0xee]);
+
+        assert_eq!(reader.read_primitive::<u32>(),
+                   Err(ReadBlobError::Truncated { expected: 4, remaining: 2 }));
+        assert_eq!(reader.remaining(), &[0xff, 0xee]);
+
+        assert_eq!(reader.read_primitive::<bool>(),
+                   Err(ReadBlobError::Decode(DecodeBoolError)));
+        assert_eq!(reader.remaining(), &[0xff, 0xee]);
+    }
+}
@@ -52,6 +52,18 @@ impl<T> IntoOwned for [T] {
 mod test {
     use super::*;
 
+    use std::cell::Cell;
+
+    /// Counts drops in a shared cell, with an id to tell values apart.
+    #[derive(Debug)]
+    pub(crate) struct DropCounter<'a>(pub u8, pub &'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
     #[test]
     fn slice_into_owned() {
         // FIXME: actually count # of drops
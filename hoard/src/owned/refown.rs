@@ -1,7 +1,7 @@
 use core::fmt;
 use core::marker::PhantomData;
 use core::mem::ManuallyDrop;
-use core::ops::{Deref, DerefMut};
+use core::ops::{Deref, DerefMut, Range};
 use core::ptr::NonNull;
 use core::ptr;
 
@@ -29,6 +29,44 @@ impl<'a, T: 'a + ?Sized> RefOwn<'a, T> {
     }
 }
 
+impl<'a, T> RefOwn<'a, [T]> {
+    /// Divides one owned slice into two at an index.
+    ///
+    /// Ownership of the elements is transferred to the two halves: nothing is dropped, and each
+    /// element will be dropped exactly once, by whichever half it ends up in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`, in which case all elements are dropped.
+    #[track_caller]
+    pub fn split_at(self, mid: usize) -> (Self, Self) {
+        assert!(mid <= self.len(), "mid out of bounds");
+
+        let slice: &'a mut [T] = Self::leak(self);
+        let (left, right) = slice.split_at_mut(mid);
+
+        // SAFETY: we own the slice, and the two halves are disjoint.
+        unsafe {
+            (Self::new_unchecked(left), Self::new_unchecked(right))
+        }
+    }
+
+    /// Returns an owned sub-slice.
+    ///
+    /// Ownership of the elements in `range` is transferred to the returned sub-slice; the
+    /// elements outside of `range` are dropped. If `range` is out of bounds, `None` is returned
+    /// and all elements are dropped.
+    pub fn get(self, range: Range<usize>) -> Option<Self> {
+        if range.start > range.end || range.end > self.len() {
+            None
+        } else {
+            let (_head, rest) = self.split_at(range.start);
+            let (middle, _tail) = rest.split_at(range.end - range.start);
+            Some(middle)
+        }
+    }
+}
+
 impl<T: ?Sized> Drop for RefOwn<'_, T> {
     fn drop(&mut self) {
         unsafe { ptr::drop_in_place(self.ptr.as_ptr()) }
@@ -71,7 +109,64 @@ impl<T: ?Sized + fmt::Debug> fmt::Debug for RefOwn<'_, T> {
 mod tests {
     use super::*;
 
+    use std::cell::Cell;
+
+    use crate::owned::IntoOwned;
+    use crate::owned::test::DropCounter;
+
     #[test]
     fn test() {
     }
+
+    #[test]
+    fn split_at() {
+        let drops = Cell::new(0);
+
+        let slice = [DropCounter(0, &drops), DropCounter(1, &drops), DropCounter(2, &drops)];
+        let mut slice = ManuallyDrop::new(slice);
+
+        // SAFETY: can take ownership because of ManuallyDrop
+        let owned = unsafe { RefOwn::new_unchecked(&mut slice[..]) };
+
+        let (left, right) = owned.split_at(1);
+        let left: Vec<DropCounter> = left.into_owned();
+        let right: Vec<DropCounter> = right.into_owned();
+        assert_eq!(drops.get(), 0);
+
+        assert_eq!(left.iter().map(|d| d.0).collect::<Vec<u8>>(), &[0]);
+        assert_eq!(right.iter().map(|d| d.0).collect::<Vec<u8>>(), &[1, 2]);
+
+        drop(left);
+        assert_eq!(drops.get(), 1);
+        drop(right);
+        assert_eq!(drops.get(), 3);
+
+        let mut bytes = [1u8, 2, 3, 4];
+        let owned = unsafe { RefOwn::new_unchecked(&mut bytes[..]) };
+        let (left, right) = owned.split_at(4);
+        assert_eq!(left.into_owned(), &[1, 2, 3, 4]);
+        assert_eq!(right.into_owned(), &[]);
+    }
+
+    #[test]
+    fn get() {
+        let drops = Cell::new(0);
+
+        let slice = [DropCounter(0, &drops), DropCounter(1, &drops), DropCounter(2, &drops), DropCounter(3, &drops)];
+        let mut slice = ManuallyDrop::new(slice);
+
+        // SAFETY: can take ownership because of ManuallyDrop
+        let owned = unsafe { RefOwn::new_unchecked(&mut slice[..]) };
+
+        let middle: Vec<DropCounter> = owned.get(1 .. 3).unwrap().into_owned();
+        assert_eq!(drops.get(), 2);
+        assert_eq!(middle.iter().map(|d| d.0).collect::<Vec<u8>>(), &[1, 2]);
+
+        drop(middle);
+        assert_eq!(drops.get(), 4);
+
+        let mut bytes = [1u8, 2, 3];
+        let owned = unsafe { RefOwn::new_unchecked(&mut bytes[..]) };
+        assert!(owned.get(2 .. 4).is_none());
+    }
 }
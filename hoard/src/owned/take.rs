@@ -1,3 +1,4 @@
+use std::alloc::{self, Layout};
 use core::mem::ManuallyDrop;
 use core::ptr;
 
@@ -44,3 +45,68 @@ unsafe impl<T> Take<[T]> for Vec<T> {
         }
     }
 }
+
+unsafe impl<T: ?Sized> Take<T> for Box<T> {
+    fn take_unsized<F, R>(self, f: F) -> R
+        where F: FnOnce(RefOwn<T>) -> R
+    {
+        /// Frees the box allocation, without dropping its contents.
+        struct Dealloc(*mut u8, Layout);
+
+        impl Drop for Dealloc {
+            fn drop(&mut self) {
+                if self.1.size() > 0 {
+                    unsafe { alloc::dealloc(self.0, self.1) }
+                }
+            }
+        }
+
+        let ptr: *mut T = Box::into_raw(self);
+
+        // SAFETY: the RefOwn takes ownership of the contents, while the Dealloc guard frees the
+        // allocation itself once f returns, or panics.
+        unsafe {
+            let _dealloc = Dealloc(ptr.cast(), Layout::for_value(&*ptr));
+            f(RefOwn::new_unchecked(&mut *ptr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::cell::Cell;
+
+    use crate::owned::IntoOwned;
+    use crate::owned::test::DropCounter;
+
+    #[test]
+    fn box_take_unsized() {
+        let drops = Cell::new(0);
+
+        let boxed: Box<[DropCounter]> = vec![DropCounter(0, &drops), DropCounter(1, &drops), DropCounter(2, &drops)]
+                                            .into_boxed_slice();
+        let v: Vec<DropCounter> = boxed.take_unsized(|src: RefOwn<[DropCounter]>| src.into_owned());
+        assert_eq!(v.len(), 3);
+        assert_eq!(drops.get(), 0);
+
+        drop(v);
+        assert_eq!(drops.get(), 3);
+
+        let boxed: Box<[u8]> = vec![1u8, 2, 3].into_boxed_slice();
+        let v: Vec<u8> = boxed.take_unsized(|src: RefOwn<[u8]>| src.into_owned());
+        assert_eq!(v, &[1, 2, 3]);
+
+        // Contents that aren't taken are dropped exactly once.
+        let boxed: Box<[DropCounter]> = vec![DropCounter(0, &drops)].into_boxed_slice();
+        Take::<[DropCounter]>::take_unsized(boxed, |_src| ());
+        assert_eq!(drops.get(), 4);
+
+        let boxed = Box::new(DropCounter(0, &drops));
+        let value: DropCounter = Take::<DropCounter>::take_sized(boxed);
+        assert_eq!(drops.get(), 4);
+        drop(value);
+        assert_eq!(drops.get(), 5);
+    }
+}
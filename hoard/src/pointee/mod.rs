@@ -1,5 +1,6 @@
 //! Functionality for working with pointer metadata.
 
+use std::alloc::Layout;
 use std::fmt;
 use std::ptr::{self, NonNull};
 
@@ -78,3 +79,68 @@ impl<T> Pointee for [T] {
         ptr::slice_from_raw_parts_mut(thin as *mut T, len)
     }
 }
+
+/// Computes the layout of a `#[repr(C)]` type made up of a sized prefix followed by a
+/// dynamically-sized trailing field.
+///
+/// Padding is inserted between the prefix and the trailing field as required by the trailing
+/// field's alignment, and the total is padded to a multiple of the overall alignment.
+#[track_caller]
+pub fn layout_of_composite(sized_prefix: Layout, trailing: Layout) -> Layout {
+    let (layout, _trailing_offset) = sized_prefix.extend(trailing).expect("layout overflow");
+    layout.pad_to_align()
+}
+
+/// Returns true if two pointers have the same address *and* the same metadata.
+pub fn same_target<T: ?Sized + Pointee>(a: *const T, b: *const T) -> bool {
+    ptr::eq(a as *const (), b as *const ())
+        && T::metadata(a) == T::metadata(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_of_composite() {
+        #[repr(C)]
+        struct Composite<const N: usize> {
+            prefix: u8,
+            trailing: [u32; N],
+        }
+
+        #[track_caller]
+        fn t<const N: usize>() {
+            let layout = super::layout_of_composite(
+                Layout::new::<u8>(),
+                Layout::array::<u32>(N).unwrap(),
+            );
+            assert_eq!(layout, Layout::new::<Composite<N>>());
+        }
+
+        t::<0>();
+        t::<1>();
+        t::<2>();
+        t::<7>();
+
+        let layout = super::layout_of_composite(Layout::new::<u8>(), Layout::array::<u32>(3).unwrap());
+        assert_eq!(layout.size(), 16);
+        assert_eq!(layout.align(), 4);
+    }
+
+    #[test]
+    fn same_target() {
+        let buf = [1u8, 2, 3, 4];
+        let a: *const [u8] = &buf[..];
+        let b: *const [u8] = &buf[.. 2];
+
+        assert!(super::same_target(a, a));
+        assert!(super::same_target(a, &buf[..]));
+        assert!(!super::same_target(a, b));
+        assert!(!super::same_target(b, &buf[1 .. 3]));
+
+        let n = 42u8;
+        assert!(super::same_target(&n, &n));
+        assert!(!super::same_target(&n, &buf[0]));
+    }
+}
@@ -0,0 +1,128 @@
+//! Packed bitsets.
+
+use super::*;
+
+use std::mem;
+
+/// A bitset packed into an integer.
+///
+/// Serializes identically to the underlying integer, so bit `i` is bit `i % 8` of byte `i / 8`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct LeBits<T>(T);
+
+impl<T> LeBits<T> {
+    pub fn new(raw: T) -> Self {
+        Self(raw)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+/// Iterator over the indices of the set bits in a `LeBits`, from lowest to highest.
+#[derive(Debug, Clone)]
+pub struct SetBits<T>(T);
+
+macro_rules! impl_bits {
+    ($($t:ty,)+) => {$(
+        impl LeBits<$t> {
+            pub const BITS: u32 = (mem::size_of::<$t>() * 8) as u32;
+
+            /// Returns bit `i`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `i` is out of range.
+            pub fn get_bit(&self, i: u32) -> bool {
+                assert!(i < Self::BITS, "bit index out of range");
+                self.0 & (1 << i) != 0
+            }
+
+            /// Sets bit `i` to `v`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `i` is out of range.
+            pub fn set_bit(&mut self, i: u32, v: bool) {
+                assert!(i < Self::BITS, "bit index out of range");
+                if v {
+                    self.0 |= 1 << i;
+                } else {
+                    self.0 &= !(1 << i);
+                }
+            }
+
+            pub fn iter(&self) -> SetBits<$t> {
+                SetBits(self.0)
+            }
+        }
+
+        impl Iterator for SetBits<$t> {
+            type Item = u32;
+
+            fn next(&mut self) -> Option<u32> {
+                if self.0 == 0 {
+                    None
+                } else {
+                    let i = self.0.trailing_zeros();
+                    self.0 &= self.0 - 1;
+                    Some(i)
+                }
+            }
+        }
+
+        impl Primitive for LeBits<$t> {
+            const BLOB_SIZE: usize = <$t as Primitive>::BLOB_SIZE;
+            type DecodeBytesError = !;
+
+            #[inline(always)]
+            fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+                dst.write_bytes(&self.0.to_le_bytes())
+            }
+
+            #[inline(always)]
+            fn decode_blob_bytes(blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+                let mut buf = [0; mem::size_of::<$t>()];
+                buf.copy_from_slice(&blob);
+                Ok(Self(<$t>::from_le_bytes(buf)))
+            }
+        }
+    )+}
+}
+
+impl_bits! {
+    u8, u16, u32, u64, u128,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_bits() {
+        let mut bits = LeBits::<u32>::default();
+        assert_eq!(bits.iter().count(), 0);
+
+        bits.set_bit(0, true);
+        bits.set_bit(31, true);
+        assert!(bits.get_bit(0));
+        assert!(!bits.get_bit(1));
+        assert!(bits.get_bit(31));
+
+        assert_eq!(bits.to_blob_bytes(), &[0x01, 0x00, 0x00, 0x80]);
+        assert_eq!(bits.into_inner(), 0x8000_0001);
+        assert_eq!(bits.iter().collect::<Vec<u32>>(), &[0, 31]);
+
+        bits.set_bit(0, false);
+        assert_eq!(bits.to_blob_bytes(), &[0x00, 0x00, 0x00, 0x80]);
+        assert_eq!(bits.iter().collect::<Vec<u32>>(), &[31]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_range() {
+        LeBits::<u8>::default().get_bit(8);
+    }
+}
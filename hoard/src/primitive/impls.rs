@@ -3,8 +3,10 @@ use super::*;
 use thiserror::Error;
 
 use std::convert::TryFrom;
+use std::marker::PhantomData;
 use std::mem;
 use std::num;
+use std::time::Duration;
 
 impl Primitive for ! {
     const BLOB_SIZE: usize = 0;
@@ -60,6 +62,62 @@ impl Primitive for bool {
     }
 }
 
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[error("FIXME")]
+pub struct DecodeCharError(pub u32);
+
+impl Primitive for char {
+    const BLOB_SIZE: usize = 4;
+    type DecodeBytesError = DecodeCharError;
+
+    #[inline(always)]
+    fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+        dst.write_bytes(&u32::from(*self).to_le_bytes())
+    }
+
+    #[inline(always)]
+    fn decode_blob_bytes(blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+        let raw = u32::from_le_bytes(TryFrom::try_from(&*blob).unwrap());
+        char::from_u32(raw).ok_or(DecodeCharError(raw))
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[error("FIXME")]
+pub struct DecodeDurationError {
+    pub nanos: u32,
+}
+
+/// Encoded as seconds, as a `u64`, followed by nanoseconds, as a `u32`.
+impl Primitive for Duration {
+    const BLOB_SIZE: usize = 12;
+    type DecodeBytesError = DecodeDurationError;
+
+    #[inline(always)]
+    fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+        dst.write_struct()
+           .write_field(&self.as_secs())
+           .write_field(&self.subsec_nanos())
+           .done()
+    }
+
+    #[inline(always)]
+    fn decode_blob_bytes(blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+        let mut fields = blob.struct_fields();
+        let secs: u64 = fields.trust_field().into_ok();
+        let nanos: u32 = fields.trust_field().into_ok();
+        fields.assert_done();
+
+        if nanos < 1_000_000_000 {
+            Ok(Duration::new(secs, nanos))
+        } else {
+            Err(DecodeDurationError { nanos })
+        }
+    }
+}
+
 macro_rules! impl_ints {
     ($($t:ty,)+) => {$(
         impl Primitive for $t {
@@ -97,6 +155,7 @@ macro_rules! impl_nonzero_ints {
     ($($n:ty => $t:ty, )+) => {$(
         impl Primitive for $t {
             const BLOB_SIZE: usize = mem::size_of::<$t>();
+            const NONZERO_NICHE: bool = true;
             type DecodeBytesError = DecodeNonZeroIntError;
 
             #[inline(always)]
@@ -118,3 +177,87 @@ impl_nonzero_ints! {
     u8 => num::NonZeroU8, u16 => num::NonZeroU16, u32 => num::NonZeroU32, u64 => num::NonZeroU64, u128 => num::NonZeroU128,
     i8 => num::NonZeroI8, i16 => num::NonZeroI16, i32 => num::NonZeroI32, i64 => num::NonZeroI64, i128 => num::NonZeroI128,
 }
+
+impl<T: ?Sized + 'static> Primitive for PhantomData<T> {
+    const BLOB_SIZE: usize = 0;
+    type DecodeBytesError = !;
+
+    #[inline(always)]
+    fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+        dst.write_bytes(&[])
+    }
+
+    #[inline(always)]
+    fn decode_blob_bytes(_blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+        Ok(PhantomData)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phantomdata() {
+        type Marked = (u8, PhantomData<Vec<u8>>, u32);
+
+        assert_eq!(<PhantomData<Vec<u8>> as Blob>::SIZE, 0);
+        assert_eq!(<Marked as Blob>::SIZE, <u8 as Blob>::SIZE + <u32 as Blob>::SIZE);
+
+        let v: Marked = (0x42, PhantomData, 0x1234_5678);
+        let bytes = v.to_blob_bytes();
+        assert_eq!(bytes, &[0x42, 0x78, 0x56, 0x34, 0x12]);
+
+        let bytes = Bytes::<Marked>::try_from(&bytes[..]).unwrap();
+        assert_eq!(<Marked as Blob>::decode_bytes(bytes).unwrap().trust(), v);
+    }
+
+    #[test]
+    fn nonzero() {
+        let n = num::NonZeroU32::new(0x1234_5678).unwrap();
+        assert_eq!(n.to_blob_bytes(), &[0x78, 0x56, 0x34, 0x12]);
+
+        let bytes = Bytes::<num::NonZeroU32>::try_from(&[0x78, 0x56, 0x34, 0x12][..]).unwrap();
+        assert_eq!(num::NonZeroU32::decode_blob_bytes(bytes).unwrap(), n);
+
+        let bytes = Bytes::<num::NonZeroU32>::try_from(&[0, 0, 0, 0][..]).unwrap();
+        assert!(num::NonZeroU32::decode_blob_bytes(bytes).is_err());
+
+        let bytes = Bytes::<num::NonZeroI8>::try_from(&[0xff][..]).unwrap();
+        assert_eq!(num::NonZeroI8::decode_blob_bytes(bytes).unwrap().get(), -1);
+    }
+
+    #[test]
+    fn decode_invalid() {
+        let bytes = Bytes::<bool>::try_from(&[2][..]).unwrap();
+        assert_eq!(bool::decode_blob_bytes(bytes), Err(DecodeBoolError));
+
+        assert_eq!('€'.to_blob_bytes(), &[0xac, 0x20, 0, 0]);
+        let bytes = Bytes::<char>::try_from(&[0xac, 0x20, 0, 0][..]).unwrap();
+        assert_eq!(char::decode_blob_bytes(bytes), Ok('€'));
+
+        // Surrogates and values above 0x10FFFF aren't scalar values.
+        let bytes = Bytes::<char>::try_from(&[0x00, 0xd8, 0, 0][..]).unwrap();
+        assert_eq!(char::decode_blob_bytes(bytes), Err(DecodeCharError(0xd800)));
+
+        let bytes = Bytes::<char>::try_from(&[0x00, 0x00, 0x11, 0][..]).unwrap();
+        assert_eq!(char::decode_blob_bytes(bytes), Err(DecodeCharError(0x11_0000)));
+    }
+
+    #[test]
+    fn duration() {
+        let d = Duration::new(1_700_000_000, 500_000_000);
+        let bytes = d.to_blob_bytes();
+        assert_eq!(bytes, &[0x00, 0xf1, 0x53, 0x65, 0, 0, 0, 0,
+                            0x00, 0x65, 0xcd, 0x1d]);
+
+        let bytes = Bytes::<Duration>::try_from(&bytes[..]).unwrap();
+        assert_eq!(Duration::decode_blob_bytes(bytes), Ok(d));
+
+        let mut bytes = d.to_blob_bytes();
+        bytes[8 ..].copy_from_slice(&2_000_000_000u32.to_le_bytes());
+        let bytes = Bytes::<Duration>::try_from(&bytes[..]).unwrap();
+        assert_eq!(Duration::decode_blob_bytes(bytes),
+                   Err(DecodeDurationError { nanos: 2_000_000_000 }));
+    }
+}
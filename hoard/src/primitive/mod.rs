@@ -7,17 +7,22 @@ use crate::load::Load;
 use crate::save::{Save, SavePoll, Saver};
 
 pub mod impls;
+pub mod bits;
 
 pub trait Primitive : 'static + Copy {
     type DecodeBytesError : 'static + std::error::Error + Send;
     const BLOB_SIZE: usize;
 
+    /// See `Blob::NONZERO_NICHE`.
+    const NONZERO_NICHE: bool = false;
+
     fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self>;
     fn decode_blob_bytes(blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError>;
 }
 
 impl<T: Primitive> Blob for T {
     const SIZE: usize = T::BLOB_SIZE;
+    const NONZERO_NICHE: bool = <T as Primitive>::NONZERO_NICHE;
     type DecodeBytesError = T::DecodeBytesError;
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
@@ -0,0 +1,109 @@
+//! Caching of loaded values.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+
+use super::*;
+
+use crate::load::Load;
+
+/// Memoizes the values loaded from clean pointers.
+///
+/// Values are cached by pointer blob, e.g. `Offset`, so a cache should only be used with pointers
+/// into a single zone. Dirty values are never cached, as they can be mutated.
+pub struct LoadCache<P: Ptr, T> {
+    cache: RefCell<HashMap<P::Blob, Arc<T>>>,
+}
+
+impl<P: Ptr, T> Default for LoadCache<P, T> {
+    fn default() -> Self {
+        Self {
+            cache: RefCell::default(),
+        }
+    }
+}
+
+impl<P: Ptr, T> LoadCache<P, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of cached values.
+    pub fn len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Returns true if nothing has been cached.
+    pub fn is_empty(&self) -> bool {
+        self.cache.borrow().is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.get_mut().clear()
+    }
+}
+
+impl<P: Ptr, T: Load + Clone> LoadCache<P, T>
+where P::Blob: Hash + Eq,
+      P::Zone: AsZone<T::Zone>,
+{
+    /// Gets the value in a bag, loading it only if it isn't already cached.
+    pub fn get_cached(&self, bag: &Bag<T, P>) -> Arc<T>
+        where P: Get
+    {
+        match bag.try_get_dirty() {
+            Ok(dirty) => Arc::new(dirty.clone()),
+            Err(clean) => {
+                let blob = clean.to_blob();
+                if let Some(cached) = self.cache.borrow().get(&blob) {
+                    return Arc::clone(cached);
+                }
+
+                let value = match bag.get() {
+                    Ref::Borrowed(value) => value.clone(),
+                    Ref::Owned(value) => value,
+                };
+                let value = Arc::new(value);
+                self.cache.borrow_mut().insert(blob, Arc::clone(&value));
+                value
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::ptr::key::{KeyMut, Offset};
+
+    #[test]
+    fn get_cached() {
+        let map: &[u8] = &[0x78, 0x56, 0x34, 0x12, 1, 0, 0, 0];
+        let bag_at = |offset| -> Bag<u32, Key<[u8]>> {
+            unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(offset), map), ()) }
+        };
+
+        let cache = LoadCache::new();
+        let a = cache.get_cached(&bag_at(0));
+        assert_eq!(*a, 0x1234_5678);
+
+        let b = cache.get_cached(&bag_at(0));
+        assert!(Arc::ptr_eq(&a, &b));
+
+        let c = cache.get_cached(&bag_at(4));
+        assert_eq!(*c, 1);
+        assert_eq!(cache.len(), 2);
+
+        // Dirty values aren't cached.
+        let cache = LoadCache::new();
+        let dirty: Bag<u32, KeyMut<[u8]>> = KeyMut::alloc(42u32);
+        let a = cache.get_cached(&dirty);
+        let b = cache.get_cached(&dirty);
+        assert_eq!(*a, 42);
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -1,14 +1,85 @@
+use std::any::type_name;
 use std::ptr::NonNull;
 use std::alloc::Layout;
+use std::marker::PhantomData;
 use std::mem;
 
+#[cfg(any(test, feature = "heap-stats"))]
+use std::cell::Cell;
+
 use super::*;
 
+#[cfg(any(test, feature = "heap-stats"))]
+thread_local! {
+    static LIVE_ALLOCATIONS: Cell<usize> = Cell::new(0);
+}
+
+/// In debug builds, values are allocated with their metadata immediately before them, to catch
+/// incorrect metadata.
 #[derive(Debug)]
 pub struct Heap {
     raw: NonNull<()>,
 }
 
+/// Allocates `Bag`s on the heap.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeapAlloc;
+
+impl Alloc for HeapAlloc {
+    type Ptr = Heap;
+
+    #[inline]
+    fn alloc<T: ?Sized + Pointee>(&mut self, src: impl Take<T>) -> Bag<T, Heap> {
+        Heap::alloc(src)
+    }
+
+    /// Initializes the slice in place, without an intermediate `Vec`.
+    fn alloc_slice<T, I>(&mut self, len: usize, iter: I) -> Bag<[T], Heap>
+        where I: IntoIterator<Item = T>
+    {
+        /// Drops the initialized items, and frees the allocation, if the iterator panics or
+        /// yields the wrong number of items.
+        struct Partial<T> {
+            raw: NonNull<()>,
+            layout: Layout,
+            initialized: usize,
+            marker: PhantomData<T>,
+        }
+
+        impl<T> Drop for Partial<T> {
+            fn drop(&mut self) {
+                unsafe {
+                    let items = std::ptr::slice_from_raw_parts_mut(self.raw.cast::<T>().as_ptr(), self.initialized);
+                    std::ptr::drop_in_place(items);
+                    Heap::heap_dealloc::<[T]>(self.raw, self.layout)
+                }
+            }
+        }
+
+        let layout = Layout::array::<T>(len).expect("slice layout overflow");
+        let mut partial = Partial::<T> {
+            raw: unsafe { Heap::heap_alloc::<[T]>(layout, len) },
+            layout,
+            initialized: 0,
+            marker: PhantomData,
+        };
+
+        for item in iter {
+            assert!(partial.initialized < len, "iterator yielded the wrong number of items");
+            unsafe {
+                partial.raw.cast::<T>().as_ptr().add(partial.initialized).write(item);
+            }
+            partial.initialized += 1;
+        }
+        assert_eq!(partial.initialized, len, "iterator yielded the wrong number of items");
+
+        let partial = mem::ManuallyDrop::new(partial);
+        unsafe {
+            Bag::from_raw_parts(Heap { raw: partial.raw }, len)
+        }
+    }
+}
+
 impl Default for Heap {
     #[inline]
     fn default() -> Self {
@@ -17,19 +88,75 @@ impl Default for Heap {
 }
 
 impl Heap {
+    /// Returns the number of live heap allocations made by the current thread.
+    ///
+    /// Only available in tests, or with the `heap-stats` feature, to check that values are
+    /// deallocated correctly.
+    #[cfg(any(test, feature = "heap-stats"))]
+    pub fn live_allocations() -> usize {
+        LIVE_ALLOCATIONS.with(Cell::get)
+    }
+
+    /// Checks that `metadata` is the metadata the value was allocated with.
+    ///
+    /// Incorrect metadata is a bug elsewhere, and would otherwise be undefined behavior. So the
+    /// metadata is compared directly, before anything is computed from it.
+    #[inline(always)]
+    #[track_caller]
+    fn debug_check_metadata<T: ?Sized + Pointee>(&self, metadata: T::Metadata) {
+        #[cfg(debug_assertions)]
+        {
+            let allocated = unsafe {
+                let ptr = self.raw.as_ptr().cast::<u8>().sub(mem::size_of::<T::Metadata>());
+                ptr.cast::<T::Metadata>().read_unaligned()
+            };
+            assert_eq!(metadata, allocated,
+                       "Heap metadata mismatch: {:?} for {}", metadata, type_name::<T>());
+        }
+    }
+
+    /// Returns the layout of the allocation for a value, and the offset of the value within it.
+    ///
+    /// In debug builds the value is preceded by its metadata.
+    #[inline(always)]
+    fn alloc_layout<T: ?Sized + Pointee>(layout: Layout) -> (Layout, usize) {
+        if cfg!(debug_assertions) {
+            Layout::new::<T::Metadata>().extend(layout).expect("layout overflow")
+        } else {
+            (layout, 0)
+        }
+    }
+
     #[inline]
-    unsafe fn heap_dealloc(ptr: NonNull<()>, layout: Layout) {
+    unsafe fn heap_dealloc<T: ?Sized + Pointee>(ptr: NonNull<()>, layout: Layout) {
+        #[cfg(any(test, feature = "heap-stats"))]
+        LIVE_ALLOCATIONS.with(|n| n.set(n.get().wrapping_sub(1)));
+
+        let (layout, offset) = Self::alloc_layout::<T>(layout);
         if layout.size() > 0 {
-            std::alloc::dealloc(ptr.cast().as_ptr(), layout)
+            std::alloc::dealloc(ptr.as_ptr().cast::<u8>().sub(offset), layout)
         }
     }
 
     #[inline]
-    unsafe fn heap_alloc(layout: Layout) -> NonNull<()> {
+    unsafe fn heap_alloc<T: ?Sized + Pointee>(layout: Layout, metadata: T::Metadata) -> NonNull<()> {
+        #[cfg(any(test, feature = "heap-stats"))]
+        LIVE_ALLOCATIONS.with(|n| n.set(n.get().wrapping_add(1)));
+
+        let (layout, offset) = Self::alloc_layout::<T>(layout);
         if layout.size() > 0 {
             let ptr = std::alloc::alloc(layout);
-            NonNull::new(ptr.cast())
-                    .unwrap_or_else(|| std::alloc::handle_alloc_error(layout))
+            if ptr.is_null() {
+                std::alloc::handle_alloc_error(layout)
+            }
+            let ptr = ptr.add(offset);
+
+            #[cfg(debug_assertions)]
+            ptr.sub(mem::size_of::<T::Metadata>())
+               .cast::<T::Metadata>()
+               .write_unaligned(metadata);
+
+            NonNull::new_unchecked(ptr.cast())
         } else {
             NonNull::new_unchecked(layout.align() as *mut ())
         }
@@ -57,17 +184,19 @@ impl Ptr for Heap {
         let r = self.try_get_dirty_mut::<T>(metadata).into_ok().trust();
         let layout = Layout::for_value(r);
         std::ptr::drop_in_place::<T>(r);
-        Self::heap_dealloc(NonNull::from(r).cast(), layout)
+        Self::heap_dealloc::<T>(NonNull::from(r).cast(), layout)
     }
 
     #[inline(always)]
     unsafe fn try_get_dirty<T: ?Sized + Pointee>(&self, metadata: T::Metadata) -> Result<MaybeValid<&T>, Self::Clean> {
+        self.debug_check_metadata::<T>(metadata);
         let ptr = T::make_fat_ptr_mut(self.raw.as_ptr(), metadata);
         Ok((&*ptr).into())
     }
 
     #[inline(always)]
     unsafe fn try_get_dirty_mut<T: ?Sized + Pointee>(&mut self, metadata: T::Metadata) -> Result<MaybeValid<&mut T>, Self::Clean> {
+        self.debug_check_metadata::<T>(metadata);
         let ptr = T::make_fat_ptr_mut(self.raw.as_ptr(), metadata);
         Ok((&mut *ptr).into())
     }
@@ -75,23 +204,25 @@ impl Ptr for Heap {
     unsafe fn try_take_dirty_then<T: ?Sized + Pointee, F, R>(self, metadata: T::Metadata, f: F) -> Result<R, Self::Clean>
         where F: FnOnce(MaybeValid<RefOwn<T>>) -> R
     {
+        self.debug_check_metadata::<T>(metadata);
         let ptr = T::make_fat_ptr_mut(self.raw.as_ptr(), metadata);
         let src: &mut T = &mut *(ptr as *mut _);
         let layout = Layout::for_value(src);
 
-        struct DeallocOnDrop {
+        struct DeallocOnDrop<T: ?Sized + Pointee> {
             ptr: NonNull<()>,
             layout: Layout,
+            marker: PhantomData<*const T>,
         }
 
-        impl Drop for DeallocOnDrop {
+        impl<T: ?Sized + Pointee> Drop for DeallocOnDrop<T> {
             #[inline(always)]
             fn drop(&mut self) {
-                unsafe { Heap::heap_dealloc(self.ptr, self.layout) }
+                unsafe { Heap::heap_dealloc::<T>(self.ptr, self.layout) }
             }
         }
 
-        let dropper = DeallocOnDrop { ptr: self.raw, layout };
+        let dropper = DeallocOnDrop::<T> { ptr: self.raw, layout, marker: PhantomData };
         let src: RefOwn<T> = RefOwn::new_unchecked(src);
 
         Ok(f(src.into()))
@@ -103,7 +234,7 @@ impl Ptr for Heap {
             let layout = Layout::for_value::<T>(&*src);
 
             unsafe {
-                let dst = Self::heap_alloc(layout);
+                let dst = Self::heap_alloc::<T>(layout, metadata);
                 std::ptr::copy_nonoverlapping::<u8>(
                     &*src as *const T as *const u8,
                     dst.as_ptr().cast(),
@@ -189,6 +320,81 @@ mod tests {
         let bag = Heap::alloc(42u8);
     }
 
+    #[test]
+    fn live_allocations() {
+        let initial = Heap::live_allocations();
+
+        let bag = Heap::alloc(Heap::alloc(42u8));
+        assert_eq!(Heap::live_allocations(), initial + 2);
+
+        let unit = Heap::alloc(());
+        assert_eq!(Heap::live_allocations(), initial + 3);
+        drop(unit);
+
+        let inner = bag.try_take_dirty().into_ok();
+        assert_eq!(Heap::live_allocations(), initial + 1);
+
+        drop(inner);
+        assert_eq!(Heap::live_allocations(), initial);
+    }
+
+    #[test]
+    fn new_in() {
+        let initial = Heap::live_allocations();
+
+        let bag = Bag::new_in(42u8, &mut HeapAlloc);
+        assert_eq!(Heap::live_allocations(), initial + 1);
+        assert_eq!(bag.try_get_dirty().into_ok(), &42u8);
+
+        drop(bag);
+        assert_eq!(Heap::live_allocations(), initial);
+    }
+
+    #[test]
+    fn alloc_slice() {
+        let initial = Heap::live_allocations();
+
+        let bag = HeapAlloc.alloc_slice(4, 0u8 .. 4);
+        assert_eq!(Heap::live_allocations(), initial + 1);
+        assert_eq!(bag.try_get_dirty().into_ok(), &[0, 1, 2, 3]);
+
+        let v: Vec<u8> = bag.try_take_dirty().into_ok();
+        assert_eq!(v, &[0, 1, 2, 3]);
+        assert_eq!(Heap::live_allocations(), initial);
+
+        let bag = HeapAlloc.alloc_slice(2, vec![String::from("a"), String::from("b")]);
+        assert_eq!(bag.try_get_dirty().into_ok(), &["a", "b"]);
+        drop(bag);
+        assert_eq!(Heap::live_allocations(), initial);
+
+        // Wrong lengths free the partially initialized allocation.
+        for &(len, n) in &[(4, 3), (4, 5), (0, 1)] {
+            let r = std::panic::catch_unwind(|| {
+                HeapAlloc.alloc_slice(len, (0 .. n).map(|i| i.to_string()))
+            });
+            assert!(r.is_err());
+            assert_eq!(Heap::live_allocations(), initial);
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Heap metadata mismatch")]
+    fn metadata_mismatch() {
+        let bag: Bag<[u8], Heap> = Heap::alloc(vec![1u8, 2, 3]);
+        let (ptr, len) = bag.into_raw_parts();
+        assert_eq!(len, 3);
+
+        // Leaked, as dropping it would panic again while unwinding.
+        let bag = mem::ManuallyDrop::new(unsafe { Bag::<[u8], Heap>::from_raw_parts(ptr, 4) });
+        let _ = bag.try_get_dirty();
+    }
+
+    #[test]
+    fn size_independent_of_profile() {
+        assert_eq!(mem::size_of::<Heap>(), mem::size_of::<usize>());
+    }
+
     #[test]
     fn zero_sized_does_not_alloc() {
         let bag = Heap::alloc(());
@@ -6,6 +6,7 @@ use std::ptr::NonNull;
 use thiserror::Error;
 
 use crate::blob::{BlobDyn, Bytes};
+use crate::blob::checksum::{ChecksumError, verify_checksum};
 
 use super::*;
 
@@ -16,6 +17,15 @@ pub trait Map {
 
     fn id(&self) -> Self::Id;
 
+    /// Returns the position of a key's blob in storage, if known.
+    ///
+    /// Used to resolve batched reads in storage order. Keys without a position are read in the
+    /// order requested.
+    #[inline]
+    fn key_offset(&self, _key: Self::Key) -> Option<u64> {
+        None
+    }
+
     fn get_blob_with<T: ?Sized, F, R>(&self, key: Self::Key, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
         where F: FnOnce(Bytes<T>) -> R,
               T: BlobDyn;
@@ -31,6 +41,11 @@ impl<M: ?Sized + Map> Map for &'_ M {
         (**self).id()
     }
 
+    #[inline]
+    fn key_offset(&self, key: Self::Key) -> Option<u64> {
+        (**self).key_offset(key)
+    }
+
     fn get_blob_with<T: ?Sized, F, R>(&self, key: Self::Key, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
         where F: FnOnce(Bytes<T>) -> R,
               T: BlobDyn
@@ -61,6 +76,11 @@ impl Map for [u8] {
         SliceId(self.into())
     }
 
+    #[inline]
+    fn key_offset(&self, offset: Offset) -> Option<u64> {
+        Some(offset.get())
+    }
+
     fn get_blob_with<T: ?Sized, F, R>(&self, offset: Offset, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
         where F: FnOnce(Bytes<T>) -> R,
               T: BlobDyn
@@ -77,6 +97,93 @@ impl Map for [u8] {
     }
 }
 
+/// A byte slice addressed with compact `Offset32` keys.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Slice32([u8]);
+
+impl Slice32 {
+    pub fn new(buf: &[u8]) -> &Self {
+        // SAFETY: #[repr(transparent)]
+        unsafe { &*(buf as *const [u8] as *const Self) }
+    }
+}
+
+impl AsRef<[u8]> for Slice32 {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Map for Slice32 {
+    type Id = SliceId;
+    type Error = SliceError;
+    type Key = Offset32;
+
+    #[inline]
+    fn id(&self) -> Self::Id {
+        self.0.id()
+    }
+
+    #[inline]
+    fn key_offset(&self, offset: Offset32) -> Option<u64> {
+        Some(offset.into())
+    }
+
+    fn get_blob_with<T: ?Sized, F, R>(&self, offset: Offset32, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
+        where F: FnOnce(Bytes<T>) -> R,
+              T: BlobDyn
+    {
+        self.0.get_blob_with(offset.into(), metadata, f)
+    }
+}
+
+/// A byte slice with a verified checksum trailer, as written by a `ChecksummedWriter`.
+///
+/// Keys are offsets into the data, excluding the trailer.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Checksummed([u8]);
+
+impl Checksummed {
+    /// Verifies the checksum trailer of `buf`.
+    pub fn verify(buf: &[u8]) -> Result<&Self, ChecksumError> {
+        let data = verify_checksum(buf)?;
+
+        // SAFETY: #[repr(transparent)]
+        Ok(unsafe { &*(data as *const [u8] as *const Self) })
+    }
+}
+
+impl AsRef<[u8]> for Checksummed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Map for Checksummed {
+    type Id = SliceId;
+    type Error = SliceError;
+    type Key = Offset;
+
+    #[inline]
+    fn id(&self) -> Self::Id {
+        self.0.id()
+    }
+
+    #[inline]
+    fn key_offset(&self, offset: Offset) -> Option<u64> {
+        Some(offset.get())
+    }
+
+    fn get_blob_with<T: ?Sized, F, R>(&self, offset: Offset, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
+        where F: FnOnce(Bytes<T>) -> R,
+              T: BlobDyn
+    {
+        self.0.get_blob_with(offset, metadata, f)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,4 +203,16 @@ mod tests {
         assert_eq!(buf.get_blob_with::<u16, _, _>(Offset::new(2), (), |_| ()).unwrap_err(),
                    SliceError);
     }
+
+    #[test]
+    fn slice32_map() {
+        let buf = Slice32::new(&[0x12u8, 0x34u8, 0x56u8]);
+
+        buf.get_blob_with::<u16, _, _>(Offset32::new(1), (), |src| {
+            assert_eq!(&*src, &[0x34, 0x56]);
+        }).unwrap();
+
+        assert_eq!(buf.get_blob_with::<u16, _, _>(Offset32::new(2), (), |_| ()).unwrap_err(),
+                   SliceError);
+    }
 }
@@ -4,17 +4,25 @@ use std::mem::ManuallyDrop;
 use super::*;
 
 pub mod offset;
-pub use self::offset::Offset;
+pub use self::offset::{Offset, Offset32};
 
 pub mod map;
 pub use self::map::Map;
 
-#[derive(Debug)]
 pub struct Key<'a, M: ?Sized, K = <M as Map>::Key> {
     key: K,
     map: &'a M,
 }
 
+/// Prints only the key; the map can be arbitrarily large.
+impl<'a, M: ?Sized, K: fmt::Debug> fmt::Debug for Key<'a, M, K> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Key")
+            .field(&self.key)
+            .finish()
+    }
+}
+
 impl<'a, M: ?Sized, K: Copy> Clone for Key<'a, M, K> {
     fn clone(&self) -> Self {
         *self
@@ -28,6 +36,26 @@ impl<'a, M: ?Sized, K> From<!> for Key<'a, M, K> {
     }
 }
 
+impl<'a, M: ?Sized + Map> Key<'a, M> {
+    /// Returns the key within the map.
+    pub fn key(&self) -> M::Key {
+        self.key
+    }
+}
+
+impl<'a, M: ?Sized + Map<Key = Offset>> Key<'a, M> {
+    /// Creates a new `Key` from an offset within a map, such as the offset returned by an
+    /// `OffsetSaver`.
+    pub fn from_offset(offset: Offset, map: &'a M) -> Self {
+        Self { key: offset, map }
+    }
+
+    /// Returns the offset within the map.
+    pub fn offset(&self) -> u64 {
+        self.key.get()
+    }
+}
+
 impl<'a, M: ?Sized + Map> PtrClean for Key<'a, M> {
     type Zone = &'a M;
     type Blob = M::Key;
@@ -48,6 +76,17 @@ impl<'a, M: ?Sized + Map> PtrClean for Key<'a, M> {
     }
 }
 
+impl<'a, M: ?Sized + Map> GetBlob for Key<'a, M> {
+    type Error = M::Error;
+
+    fn get_blob_with<T: ?Sized, F, R>(&self, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
+        where T: BlobDyn,
+              F: FnOnce(Bytes<'_, T>, &Self::Zone) -> R
+    {
+        self.map.get_blob_with(self.key, metadata, |bytes| f(bytes, &self.map))
+    }
+}
+
 impl<'a, M: ?Sized + Map> TryGet for Key<'a, M> {
     type Error = Error<M::Id, M::Error>;
 
@@ -98,6 +137,28 @@ impl<'a, M: ?Sized + Map> Get for Key<'a, M> {
         self.try_take_then(metadata, f)
             .expect("fixme")
     }
+
+    unsafe fn get_many<'b, T: ?Sized>(ptrs: &[(&'b Self, T::Metadata)]) -> Vec<MaybeValid<Ref<'b, T>>>
+        where T: LoadRef,
+              Self::Zone: AsZone<T::Zone>,
+    {
+        // Resolve in storage order for locality, then put the results back in the requested order.
+        let mut idxs: Vec<usize> = (0 .. ptrs.len()).collect();
+        idxs.sort_by_key(|idx| {
+            let ptr = ptrs[*idx].0;
+            ptr.map.key_offset(ptr.key)
+        });
+
+        let mut r: Vec<(usize, MaybeValid<Ref<'b, T>>)> = idxs.into_iter().map(|idx| {
+            let (ptr, metadata) = ptrs[idx];
+            (idx, ptr.get::<T>(metadata))
+        }).collect();
+        r.sort_unstable_by_key(|(idx, _)| *idx);
+
+        r.into_iter()
+         .map(|(_, r)| r)
+         .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -273,9 +334,105 @@ mod test {
         assert_eq!(r, &0x78563412);
     }
 
+    #[test]
+    fn key_offset() {
+        let map: &[u8] = &[];
+
+        let key = Key::from_blob(Offset::new(1234), &map);
+        assert_eq!(key.offset(), 1234);
+        assert_eq!(key.key(), Offset::new(1234));
+        assert_eq!(key.to_blob(), 1234);
+
+        let key = Key::from_blob(key.to_blob(), &map);
+        assert_eq!(key.offset(), 1234);
+    }
+
+    #[test]
+    fn key_from_offset() {
+        let bag = Heap::alloc(42u8);
+        let (offset, buf) = offset::OffsetSaver::new(&[][..]).try_save(&bag).unwrap();
+
+        let key = Key::from_offset(offset, &buf[..]);
+        assert_eq!(key.offset(), 1);
+
+        let bag: Bag<Bag<u8, Key<[u8]>>, _> = unsafe { Bag::from_raw_parts(key, ()) };
+        assert_eq!(*bag.get().get(), 42);
+    }
+
+    #[test]
+    fn key_try_get_truncated() {
+        let buf: &[u8] = &[0x34, 0x12];
+        let bag: Bag<u16, Key<[u8]>> = unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(0), buf), ()) };
+        assert_eq!(*bag.try_get().unwrap(), 0x1234);
+
+        // Chop off the last byte of the u16.
+        let truncated = &buf[.. 1];
+        let bag: Bag<u16, Key<[u8]>> = unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(0), truncated), ()) };
+        assert!(bag.try_get().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn key_get_truncated() {
+        let truncated: &[u8] = &[0x34];
+        let bag: Bag<u16, Key<[u8]>> = unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(0), truncated), ()) };
+        bag.get();
+    }
+
+    #[test]
+    fn bag_debug_shallow() {
+        // Loading anything from an empty map would panic.
+        let map: &[u8] = &[];
+        let bag: Bag<u16, Key<[u8]>> = unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(0), map), ()) };
+        assert!(format!("{:?}", bag.debug_shallow()).contains("ptr: Err(Key"));
+        assert!(format!("{:?}", bag).contains("ptr: Err(Key"));
+
+        // The map itself must not be printed.
+        let map: &[u8] = &[0xab; 1000];
+        let bag: Bag<u16, Key<[u8]>> = unsafe { Bag::from_raw_parts(Key::from_offset(Offset::new(0), map), ()) };
+        let shallow = format!("{:?}", bag.debug_shallow());
+        assert!(shallow.len() < 200, "{}", shallow);
+        assert!(!shallow.contains("171"));
+
+        let bag: Bag<u16, KeyMut<[u8]>> = KeyMut::alloc(12345u16);
+        let shallow = format!("{:?}", bag.debug_shallow());
+        assert!(shallow.contains("ptr: Ok(\"dirty\")"));
+        assert!(!shallow.contains("12345"));
+    }
+
+    #[test]
+    fn key_get_many() {
+        let map: &[u8] = &[10, 11, 12, 13];
+
+        let bags: Vec<Bag<u8, Key<[u8]>>> = [3, 0, 2, 0].iter().map(|offset| unsafe {
+            Bag::from_raw_parts(Key::from_blob(Offset::new(*offset), &map), ())
+        }).collect();
+        let bags: Vec<&Bag<u8, Key<[u8]>>> = bags.iter().collect();
+
+        let many: Vec<u8> = Bag::get_many(&bags).into_iter().map(|r| *r).collect();
+        let single: Vec<u8> = bags.iter().map(|bag| *bag.get()).collect();
+        assert_eq!(many, single);
+        assert_eq!(many, &[13, 10, 12, 10]);
+    }
+
     #[test]
     fn keymut() {
         let bag: Bag<u8, KeyMut<[u8]>> = KeyMut::alloc(42u8);
         dbg!(bag.get());
     }
+
+    #[test]
+    fn keymut_get_mut_clean() {
+        let map: &[u8] = &[42];
+
+        let key = Key::from_offset(Offset::new(0), map);
+        let mut bag: Bag<u8, KeyMut<[u8]>> = unsafe { Bag::from_raw_parts(KeyMut::Key(key), ()) };
+        assert!(bag.try_get_dirty().is_err());
+
+        // Getting a clean pointer mutably loads the value onto the heap.
+        *bag.get_mut() += 1;
+        assert_eq!(bag.try_get_dirty().ok(), Some(&43));
+        assert_eq!(*bag.get(), 43);
+        assert_eq!(map, &[42]);
+    }
 }
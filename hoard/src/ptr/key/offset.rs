@@ -1,12 +1,17 @@
 use std::marker::PhantomData;
 use std::convert::TryFrom;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::cmp;
+use std::io;
+use std::ptr;
 
 use thiserror::Error;
 
 use crate::blob::{BlobDyn, Bytes, BytesUninit};
 use crate::primitive::Primitive;
-use crate::ptr::{Ptr, PtrClean, PtrBlob, AsZone, TryGet, Zone};
+use crate::ptr::{Ptr, PtrClean, PtrBlob, AsZone, GetBlob, TryGet, Zone};
 use crate::save::{SaveRef, SaveRefPoll, Saver};
 use crate::load::LoadRef;
 use crate::pointee::Pointee;
@@ -14,7 +19,7 @@ use crate::pointee::Pointee;
 use super::{Key, Map};
 
 /// Slice offset.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Offset(u64);
 
 impl Offset {
@@ -27,6 +32,18 @@ impl Offset {
     pub const fn get(self) -> u64 {
         self.0
     }
+
+    /// Shifts the offset forward by `delta`, returning `None` on overflow.
+    #[inline]
+    pub fn checked_add(self, delta: u64) -> Option<Self> {
+        self.0.checked_add(delta).map(Self)
+    }
+
+    /// Shifts the offset backward by `delta`, returning `None` on underflow.
+    #[inline]
+    pub fn checked_sub(self, delta: u64) -> Option<Self> {
+        self.0.checked_sub(delta).map(Self)
+    }
 }
 
 impl Primitive for Offset {
@@ -76,6 +93,94 @@ macro_rules! impl_cmp {
 impl_cmp! {
     Offset => u64;
     u64 => Offset;
+    Offset32 => u64;
+    u64 => Offset32;
+}
+
+/// Compact, 4-byte, slice offset.
+///
+/// Halves the pointer overhead of `Offset` when all offsets fit in 32 bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Offset32(u32);
+
+impl Offset32 {
+    #[inline]
+    pub const fn new(n: u32) -> Self {
+        Self(n)
+    }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl Primitive for Offset32 {
+    const BLOB_SIZE: usize = 4;
+    type DecodeBytesError = !;
+
+    #[inline]
+    fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+        dst.write_bytes(&self.0.to_le_bytes())
+    }
+
+    #[inline]
+    fn decode_blob_bytes(blob: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+        let buf = TryFrom::try_from(&blob[..]).unwrap();
+        Ok(Self::new(u32::from_le_bytes(buf)))
+    }
+}
+
+impl From<!> for Offset32 {
+    #[inline]
+    fn from(never: !) -> Self { never }
+}
+
+impl PtrBlob for Offset32 {
+}
+
+impl From<Offset32> for u64 {
+    #[inline]
+    fn from(offset: Offset32) -> u64 {
+        offset.0.into()
+    }
+}
+
+impl From<Offset32> for Offset {
+    #[inline]
+    fn from(offset: Offset32) -> Offset {
+        Offset::new(offset.0.into())
+    }
+}
+
+/// Pointer blobs that can be created from an offset in the saver's output.
+pub trait FromOffset : PtrBlob {
+    type Error : std::error::Error + 'static + Send;
+
+    fn try_from_offset(offset: u64) -> Result<Self, Self::Error>;
+}
+
+impl FromOffset for Offset {
+    type Error = !;
+
+    #[inline]
+    fn try_from_offset(offset: u64) -> Result<Self, !> {
+        Ok(Self::new(offset))
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("FIXME")]
+pub struct Offset32OverflowError(pub u64);
+
+impl FromOffset for Offset32 {
+    type Error = Offset32OverflowError;
+
+    #[inline]
+    fn try_from_offset(offset: u64) -> Result<Self, Self::Error> {
+        u32::try_from(offset).map(Self::new)
+                             .map_err(|_| Offset32OverflowError(offset))
+    }
 }
 
 
@@ -120,27 +225,130 @@ where M: Map<Key = Offset>
 */
 
 #[derive(Debug)]
-pub struct OffsetSaver<'m, M: ?Sized> {
+pub struct OffsetSaver<'m, M: ?Sized, Q = Offset> {
     map: &'m M,
     dst: Vec<u8>,
+    /// Blobs written so far, by the hash of their bytes, as their position in `dst` and offset.
+    dedup: Option<HashMap<u64, Vec<(usize, Q)>>>,
+    append: bool,
+    initial_offset: u64,
 }
 
 impl<'m, M: ?Sized> OffsetSaver<'m, M>
 where M: Map<Key = Offset> + AsRef<[u8]>
 {
     pub fn new(map: &'m M) -> Self {
+        Self::with_offset_type(map)
+    }
+
+    /// Creates a new `OffsetSaver` that deduplicates identical blobs.
+    ///
+    /// If a blob's bytes are identical to a blob that was already written, the offset of the
+    /// existing blob is reused. Since children are saved before their parents, identical subtrees
+    /// are only written once.
+    pub fn with_dedup(map: &'m M) -> Self {
+        Self {
+            dedup: Some(HashMap::new()),
+            ..Self::new(map)
+        }
+    }
+
+    /// Creates a new `OffsetSaver` that appends to `map`.
+    ///
+    /// Clean pointers into `map` are saved as their existing offsets rather than re-serializing
+    /// the data they point to, so only dirty data is written. The returned bytes are meant to be
+    /// appended to `map`, and offsets are relative to the start of `map`.
+    pub fn append(map: &'m M) -> Self {
+        Self {
+            append: true,
+            initial_offset: map.as_ref().len() as u64,
+            ..Self::new(map)
+        }
+    }
+}
+
+impl<'m, M: ?Sized, Q: FromOffset> OffsetSaver<'m, M, Q>
+where M: Map<Key = Offset> + AsRef<[u8]>
+{
+    /// Creates a new `OffsetSaver` that saves pointers as `Q`, such as `Offset32`.
+    pub fn with_offset_type(map: &'m M) -> Self {
         Self {
             map,
             dst: vec![],
+            dedup: None,
+            append: false,
+            initial_offset: 0,
         }
     }
 
-    pub fn try_save<T: ?Sized>(mut self, value: &T) -> Result<(Offset, Vec<u8>), Box<dyn std::error::Error>>
-        where T: SaveRef<Offset>,
+    pub fn try_save<T: ?Sized>(self, value: &T) -> Result<(Q, Vec<u8>), Box<dyn std::error::Error>>
+        where T: SaveRef<Q>,
               Key<'m, M>: From<T::PtrClean>,
               &'m M: AsZone<<T::PtrClean as PtrClean>::Zone>,
     {
+        self.try_save_with_progress(value, |_| ())
+    }
+
+    /// Saves a value, calling `progress` after every blob is saved.
+    pub fn try_save_with_progress<T: ?Sized, F>(mut self, value: &T, progress: F)
+        -> Result<(Q, Vec<u8>), Box<dyn std::error::Error>>
+        where T: SaveRef<Q>,
+              Key<'m, M>: From<T::PtrClean>,
+              &'m M: AsZone<<T::PtrClean as PtrClean>::Zone>,
+              F: FnMut(SaveProgress),
+    {
+        let mut saver = ProgressSaver {
+            inner: &mut self,
+            progress,
+            blobs_saved: 0,
+        };
+        let wrapper: &mut Wrapper<ProgressSaver<Self, F>, T::PtrClean> = Wrapper::new(&mut saver);
+
+        let mut poll = value.init_save_ref();
+        let offset = wrapper.poll_ref::<T::SaveRefPoll>(&mut poll)?;
+
+        Ok((offset, self.dst))
+    }
+}
+
+/// Progress of a save, as reported by `OffsetSaver::try_save_with_progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SaveProgress {
+    /// Bytes written so far.
+    pub bytes_written: u64,
+
+    /// Blobs saved so far, including deduplicated blobs.
+    pub blobs_saved: u64,
+}
 
+/// Saver that writes blobs to an `io::Write` as they're produced.
+///
+/// Offsets are the same as those produced by `OffsetSaver`: relative to the start of the output.
+#[derive(Debug)]
+pub struct IoSaver<'m, M: ?Sized, W> {
+    map: &'m M,
+    dst: W,
+    offset: u64,
+    buf: Vec<u8>,
+}
+
+impl<'m, M: ?Sized, W: io::Write> IoSaver<'m, M, W>
+where M: Map<Key = Offset> + AsRef<[u8]>
+{
+    pub fn new(map: &'m M, dst: W) -> Self {
+        Self {
+            map,
+            dst,
+            offset: 0,
+            buf: vec![],
+        }
+    }
+
+    pub fn try_save<T: ?Sized>(mut self, value: &T) -> Result<(Offset, W), Box<dyn std::error::Error>>
+        where T: SaveRef<Offset>,
+              Key<'m, M>: From<T::PtrClean>,
+              &'m M: AsZone<<T::PtrClean as PtrClean>::Zone>,
+    {
         let wrapper: &mut Wrapper<Self, T::PtrClean> = Wrapper::new(&mut self);
 
         let mut poll = value.init_save_ref();
@@ -150,55 +358,74 @@ where M: Map<Key = Offset> + AsRef<[u8]>
     }
 }
 
+/// Error returned by a save, or by `serialized_size`.
+#[derive(Debug, Error)]
+#[error("FIXME")]
+pub enum SaverError<MapError: std::error::Error, SaveError: std::error::Error> {
+    /// An existing blob couldn't be read.
+    Map(MapError),
+
+    /// An existing blob couldn't be decoded.
+    Decode(Box<dyn std::error::Error + Send>),
+
+    /// A blob couldn't be saved.
+    Save(SaveError),
+}
 
 trait BlobSaver {
     type MapError : std::error::Error + 'static + Send;
     type SaveError : std::error::Error + 'static + Send;
 
     type Key : PtrClean;
+    type DstPtr : PtrBlob;
 
-    fn zone(&self) -> &<Self::Key as PtrClean>::Zone;
-
+    /// Returns the existing pointer for `key` if it can be reused, or else calls `f` with the
+    /// blob's bytes, and the zone they belong to.
     fn get_blob_with<T: ?Sized, F, R>(
         &self,
         key: Self::Key,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Result<Offset, R>, Self::MapError>
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
         where T: BlobDyn,
-              F: FnOnce(Bytes<'_, T>) -> R;
+              F: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R;
 
     fn save_blob_with<T: ?Sized, F>(
         &mut self,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Offset, Self::SaveError>
+    ) -> Result<Self::DstPtr, Self::SaveError>
         where T: BlobDyn,
               F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>;
 }
 
-impl<'m, M: ?Sized> BlobSaver for OffsetSaver<'m, M>
-where M: Map
+impl<'m, M: ?Sized, Q: FromOffset> BlobSaver for OffsetSaver<'m, M, Q>
+where M: Map<Key = Offset>
 {
     type MapError = M::Error;
-    type SaveError = !;
+    type SaveError = Q::Error;
 
     type Key = Key<'m, M>;
-
-    fn zone(&self) -> &<Self::Key as PtrClean>::Zone {
-        &self.map
-    }
+    type DstPtr = Q;
 
     fn get_blob_with<T: ?Sized, F, R>(
         &self,
         key: Self::Key,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Result<Offset, R>, Self::MapError>
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
         where T: BlobDyn,
-              F: FnOnce(Bytes<'_, T>) -> R
+              F: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R
     {
-        let r = self.map.get_blob_with(key.key, metadata, f)?;
+        // Only keys into the map we're appending to can be reused; anything else, such as a key
+        // into a different map, has to be re-serialized.
+        if self.append && ptr::eq(key.map, self.map) {
+            if let Ok(offset) = Q::try_from_offset(key.key.get()) {
+                return Ok(Ok(offset));
+            }
+        }
+
+        let r = key.get_blob_with(metadata, f)?;
         Ok(Err(r))
     }
 
@@ -206,7 +433,7 @@ where M: Map
         &mut self,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Offset, Self::SaveError>
+    ) -> Result<Self::DstPtr, Self::SaveError>
         where T: BlobDyn,
               F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>
     {
@@ -219,7 +446,194 @@ where M: Map
         let dst = BytesUninit::<T>::from_bytes(dst, metadata).expect("valid metadata");
 
         f(dst);
-        Ok(Offset::new(old_len as u64))
+        let offset = match Q::try_from_offset(self.initial_offset + old_len as u64) {
+            Ok(offset) => offset,
+            Err(err) => {
+                self.dst.truncate(old_len);
+                return Err(err);
+            }
+        };
+
+        if let Some(written) = &mut self.dedup {
+            let mut hasher = DefaultHasher::new();
+            self.dst[old_len ..].hash(&mut hasher);
+
+            // Hashes can collide, so candidates are confirmed against the bytes already written.
+            let candidates = written.entry(hasher.finish()).or_default();
+            let (blob, prev) = (&self.dst[old_len ..], &self.dst[.. old_len]);
+            let existing = candidates.iter().find(|(start, _)| {
+                prev.get(*start .. *start + size) == Some(blob)
+            });
+
+            if let Some((_, existing)) = existing {
+                let existing = *existing;
+                self.dst.truncate(old_len);
+                return Ok(existing);
+            }
+            candidates.push((old_len, offset));
+        }
+
+        Ok(offset)
+    }
+}
+
+/// Wraps an `OffsetSaver`, reporting progress as blobs are saved.
+struct ProgressSaver<'a, S, F> {
+    inner: &'a mut S,
+    progress: F,
+    blobs_saved: u64,
+}
+
+impl<'a, 'm, M: ?Sized, Q: FromOffset, F> BlobSaver for ProgressSaver<'a, OffsetSaver<'m, M, Q>, F>
+where M: Map<Key = Offset>,
+      F: FnMut(SaveProgress),
+{
+    type MapError = M::Error;
+    type SaveError = Q::Error;
+
+    type Key = Key<'m, M>;
+    type DstPtr = Q;
+
+    fn get_blob_with<T: ?Sized, G, R>(
+        &self,
+        key: Self::Key,
+        metadata: T::Metadata,
+        f: G,
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
+        where T: BlobDyn,
+              G: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R
+    {
+        self.inner.get_blob_with(key, metadata, f)
+    }
+
+    fn save_blob_with<T: ?Sized, G>(
+        &mut self,
+        metadata: T::Metadata,
+        f: G,
+    ) -> Result<Self::DstPtr, Self::SaveError>
+        where T: BlobDyn,
+              G: for<'b> FnOnce(BytesUninit<'b, T>) -> Bytes<'b, T>
+    {
+        let offset = self.inner.save_blob_with(metadata, f)?;
+
+        self.blobs_saved += 1;
+        (self.progress)(SaveProgress {
+            bytes_written: self.inner.dst.len() as u64,
+            blobs_saved: self.blobs_saved,
+        });
+        Ok(offset)
+    }
+}
+
+/// Only adds up the sizes of the blobs that would be saved.
+struct SizeSaver<K, Q> {
+    marker: PhantomData<fn(K) -> Q>,
+    size: u64,
+}
+
+impl<K: GetBlob, Q: FromOffset> BlobSaver for SizeSaver<K, Q> {
+    type MapError = K::Error;
+    type SaveError = Q::Error;
+
+    type Key = K;
+    type DstPtr = Q;
+
+    fn get_blob_with<T: ?Sized, F, R>(
+        &self,
+        key: Self::Key,
+        metadata: T::Metadata,
+        f: F,
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
+        where T: BlobDyn,
+              F: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R
+    {
+        let r = key.get_blob_with(metadata, f)?;
+        Ok(Err(r))
+    }
+
+    fn save_blob_with<T: ?Sized, F>(
+        &mut self,
+        metadata: T::Metadata,
+        _f: F,
+    ) -> Result<Self::DstPtr, Self::SaveError>
+        where T: BlobDyn,
+              F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>
+    {
+        let size = T::try_size(metadata).expect("valid metadata");
+
+        let offset = Q::try_from_offset(self.size)?;
+        self.size += size as u64;
+        Ok(offset)
+    }
+}
+
+/// Computes how many bytes saving a value with `Q` pointers would produce, without encoding
+/// anything.
+///
+/// The result is the length of the buffer `OffsetSaver::new` would return. Clean pointers are
+/// followed as they are when saving, each reading from its own map, so no map is needed.
+pub fn serialized_size<Q: FromOffset, T: ?Sized>(value: &T)
+    -> Result<u64, SaverError<<T::PtrClean as GetBlob>::Error, Q::Error>>
+    where T: SaveRef<Q>,
+          T::PtrClean: GetBlob,
+{
+    let mut saver = SizeSaver {
+        marker: PhantomData,
+        size: 0,
+    };
+    let wrapper: &mut Wrapper<SizeSaver<T::PtrClean, Q>, T::PtrClean> = Wrapper::new(&mut saver);
+
+    let mut poll = value.init_save_ref();
+    wrapper.poll_ref::<T::SaveRefPoll>(&mut poll)?;
+
+    Ok(saver.size)
+}
+
+impl<'m, M: ?Sized, W: io::Write> BlobSaver for IoSaver<'m, M, W>
+where M: Map
+{
+    type MapError = M::Error;
+    type SaveError = io::Error;
+
+    type Key = Key<'m, M>;
+    type DstPtr = Offset;
+
+    fn get_blob_with<T: ?Sized, F, R>(
+        &self,
+        key: Self::Key,
+        metadata: T::Metadata,
+        f: F,
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
+        where T: BlobDyn,
+              F: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R
+    {
+        // IoSaver never appends to its map, so every blob is re-serialized. The key may be into a
+        // different map, so it's read from its own.
+        let r = key.get_blob_with(metadata, f)?;
+        Ok(Err(r))
+    }
+
+    fn save_blob_with<T: ?Sized, F>(
+        &mut self,
+        metadata: T::Metadata,
+        f: F,
+    ) -> Result<Self::DstPtr, Self::SaveError>
+        where T: BlobDyn,
+              F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>
+    {
+        let size = T::try_size(metadata).expect("valid metadata");
+
+        self.buf.clear();
+        self.buf.resize(size, 0);
+
+        let dst = BytesUninit::<T>::from_bytes(&mut self.buf[..], metadata).expect("valid metadata");
+        f(dst);
+
+        self.dst.write_all(&self.buf)?;
+
+        let offset = Offset::new(self.offset);
+        self.offset += size as u64;
+        Ok(offset)
     }
 }
 
@@ -245,28 +659,25 @@ where S::Key: From<P>,
     type SaveError = S::SaveError;
 
     type Key = P;
-
-    fn zone(&self) -> &<Self::Key as PtrClean>::Zone {
-        self.inner.zone().as_zone()
-    }
+    type DstPtr = S::DstPtr;
 
     fn get_blob_with<T: ?Sized, F, R>(
         &self,
         key: Self::Key,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Result<Offset, R>, Self::MapError>
+    ) -> Result<Result<Self::DstPtr, R>, Self::MapError>
         where T: BlobDyn,
-              F: FnOnce(Bytes<'_, T>) -> R
+              F: FnOnce(Bytes<'_, T>, &<Self::Key as PtrClean>::Zone) -> R
     {
-        self.inner.get_blob_with(key.into(), metadata, f)
+        self.inner.get_blob_with(key.into(), metadata, |bytes, zone| f(bytes, zone.as_zone()))
     }
 
     fn save_blob_with<T: ?Sized, F>(
         &mut self,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Offset, Self::SaveError>
+    ) -> Result<Self::DstPtr, Self::SaveError>
         where T: BlobDyn,
               F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>
     {
@@ -278,27 +689,27 @@ impl<S: BlobSaver, P: PtrClean> Saver for Wrapper<S, P>
 where S::Key: From<P>,
       <S::Key as PtrClean>::Zone: AsZone<P::Zone>
 {
-    type Error = Box<dyn std::error::Error>;
+    type Error = SaverError<S::MapError, S::SaveError>;
     type SrcPtr = P;
-    type DstPtr = Offset;
+    type DstPtr = S::DstPtr;
 
     fn save_ptr<T: ?Sized>(
         &mut self,
         key: Self::SrcPtr,
         metadata: T::Metadata,
-    ) -> Result<Result<Offset, T::SaveRefPoll>, Self::Error>
+    ) -> Result<Result<Self::DstPtr, T::SaveRefPoll>, Self::Error>
     where
-        T: SaveRef<Offset>,
+        T: SaveRef<Self::DstPtr>,
         <Self::SrcPtr as Ptr>::Zone: AsZone<T::Zone>,
     {
-        let r = self.get_blob_with(key, metadata, |bytes| {
-            T::init_save_ref_from_bytes(bytes, self.zone().as_zone())
-        })?;
+        let r = self.get_blob_with(key, metadata, |bytes, zone| {
+            T::init_save_ref_from_bytes(bytes, zone.as_zone())
+        }).map_err(SaverError::Map)?;
 
         match r {
             Ok(offset) => Ok(Ok(offset)),
             Err(Ok(poll)) => Ok(Err(poll)),
-            Err(Err(decode_err)) => Err(decode_err.into())
+            Err(Err(decode_err)) => Err(SaverError::Decode(Box::new(decode_err)))
         }
     }
 
@@ -329,11 +740,11 @@ where S::Key: From<P>,
         &mut self,
         metadata: T::Metadata,
         f: F,
-    ) -> Result<Offset, Self::Error>
+    ) -> Result<Self::DstPtr, Self::Error>
         where T: BlobDyn,
               F: for<'a> FnOnce(BytesUninit<'a, T>) -> Bytes<'a, T>
     {
-        Ok(BlobSaver::save_blob_with(self, metadata, f)?)
+        BlobSaver::save_blob_with(self, metadata, f).map_err(SaverError::Save)
     }
 }
 
@@ -348,6 +759,36 @@ mod tests {
     };
     use crate::bag::Bag;
 
+    type Nested = Bag<Bag<Bag<u8, Heap>, Heap>, Heap>;
+
+    /// A byte behind three heap pointers.
+    fn nested() -> Nested {
+        Heap::alloc(Heap::alloc(Heap::alloc(32u8)))
+    }
+
+    /// How `OffsetSaver::new` saves `nested()`: the byte, then each pointer in turn.
+    const NESTED_OFFSET: u64 = 17;
+    const NESTED_BYTES: &[u8] = &[
+        32,
+        0,0,0,0,0,0,0,0,
+        1,0,0,0,0,0,0,0,
+        9,0,0,0,0,0,0,0,
+    ];
+
+    #[test]
+    fn offset_checked_arith() {
+        let offset = Offset::new(10);
+        assert_eq!(offset.checked_add(5), Some(Offset::new(15)));
+        assert_eq!(offset.checked_sub(10), Some(Offset::new(0)));
+        assert_eq!(offset.checked_sub(11), None);
+
+        let max = Offset::new(u64::MAX);
+        assert_eq!(max.checked_add(0), Some(max));
+        assert_eq!(max.checked_add(1), None);
+        assert_eq!(Offset::new(u64::MAX - 1).checked_add(1), Some(max));
+        assert_eq!(max.checked_sub(u64::MAX), Some(Offset::new(0)));
+    }
+
     #[test]
     fn offset_saver_u8() {
         let map: &[u8] = &[];
@@ -381,15 +822,183 @@ mod tests {
             1,0,0,0,0,0,0,0,
         ]);
 
-        let bag = Heap::alloc(Heap::alloc(Heap::alloc(32u8)));
         let saver = OffsetSaver::new(map);
-        let (offset, buf) = saver.try_save(&bag).unwrap();
+        let (offset, buf) = saver.try_save(&nested()).unwrap();
+        assert_eq!(offset, NESTED_OFFSET);
+        assert_eq!(buf, NESTED_BYTES);
+    }
+
+    #[test]
+    fn io_saver() {
+        let map: &[u8] = &[];
+
+        let saver = IoSaver::new(map, vec![]);
+        let (offset, buf) = saver.try_save(&nested()).unwrap();
+        assert_eq!(offset, NESTED_OFFSET);
+        assert_eq!(buf, NESTED_BYTES);
+    }
+
+    #[test]
+    fn io_saver_foreign_key() {
+        let map: &[u8] = &[1, 2, 3];
+        let foreign: &[u8] = &[7];
+
+        let clean: Bag<u8, KeyMut<[u8]>> = unsafe { Bag::from_raw_parts(KeyMut::Key(Key::from_offset(Offset::new(0), foreign)), ()) };
+        let dirty = KeyMut::<[u8]>::alloc(clean);
+
+        // The 7 is read from the map the key points into, not the saver's own map.
+        let (offset, buf) = IoSaver::new(map, vec![]).try_save(&dirty).unwrap();
+        assert_eq!(buf[0], 7);
+
+        let (expected_offset, expected_buf) = OffsetSaver::new(map).try_save(&dirty).unwrap();
+        assert_eq!(offset, expected_offset);
+        assert_eq!(buf, expected_buf);
+    }
+
+    #[test]
+    fn offset_saver_append() {
+        let (offset, buf) = OffsetSaver::new(&[][..]).try_save(&Heap::alloc(42u8)).unwrap();
+        assert_eq!(offset, 1);
+
+        let map: &[u8] = &buf;
+        let clean: Bag<u8, KeyMut<[u8]>> = unsafe { Bag::from_raw_parts(KeyMut::Key(Key::from_offset(Offset::new(0), map)), ()) };
+        let dirty = KeyMut::<[u8]>::alloc(clean);
+
+        // The clean bag is saved as its existing offset, without re-writing the 42.
+        let (offset, appended) = OffsetSaver::append(map).try_save(&dirty).unwrap();
         assert_eq!(offset, 17);
-        assert_eq!(buf, &[
-            32,
+        assert_eq!(appended, &[
             0,0,0,0,0,0,0,0,
-            1,0,0,0,0,0,0,0,
             9,0,0,0,0,0,0,0,
         ]);
+
+        let (_, resaved) = OffsetSaver::new(map).try_save(&dirty).unwrap();
+        assert_eq!(resaved.len(), appended.len() + 1);
+
+        let mut full = buf.clone();
+        full.extend_from_slice(&appended);
+        let map: &[u8] = &full;
+        type Saved<'a> = Bag<Bag<Bag<u8, Key<'a, [u8]>>, Key<'a, [u8]>>, Key<'a, [u8]>>;
+        let saved: Saved = unsafe {
+            Bag::from_raw_parts(Key::from_offset(offset, map), ())
+        };
+        assert_eq!(*saved.get().get().get(), 42);
+    }
+
+    #[test]
+    fn offset_saver_append_foreign_key() {
+        let map: &[u8] = &[1, 2, 3];
+        let foreign: &[u8] = &[7];
+
+        let clean: Bag<u8, KeyMut<[u8]>> = unsafe { Bag::from_raw_parts(KeyMut::Key(Key::from_offset(Offset::new(0), foreign)), ()) };
+        let dirty = KeyMut::<[u8]>::alloc(clean);
+
+        // The key isn't into the map being appended to, so the 7 has to be re-written.
+        let (offset, appended) = OffsetSaver::append(map).try_save(&dirty).unwrap();
+        assert_eq!(offset, 12);
+        assert_eq!(appended, &[
+            7,
+            3,0,0,0,0,0,0,0,
+            4,0,0,0,0,0,0,0,
+        ]);
+    }
+
+    #[test]
+    fn offset32_saver() {
+        use crate::ptr::key::map::Slice32;
+
+        let map: &[u8] = &[];
+
+        let saver = OffsetSaver::<_, Offset32>::with_offset_type(map);
+        let (offset, buf) = saver.try_save(&nested()).unwrap();
+        assert_eq!(offset, 9);
+        assert_eq!(buf, &[
+            32,
+            0,0,0,0,
+            1,0,0,0,
+            5,0,0,0,
+        ]);
+        assert!(buf.len() < NESTED_BYTES.len());
+
+        type K<'a> = Key<'a, Slice32>;
+        type Saved<'a> = Bag<Bag<Bag<Bag<u8, K<'a>>, K<'a>>, K<'a>>, K<'a>>;
+
+        let map = Slice32::new(&buf);
+        let bag: Saved = unsafe {
+            Bag::from_raw_parts(Key::from_blob(offset, &map), ())
+        };
+        assert_eq!(*bag.get().get().get().get(), 32);
+    }
+
+    #[test]
+    fn offset32_overflow() {
+        assert_eq!(Offset32::try_from_offset(u32::MAX.into()), Ok(Offset32::new(u32::MAX)));
+        assert_eq!(Offset32::try_from_offset(u64::from(u32::MAX) + 1),
+                   Err(Offset32OverflowError(u64::from(u32::MAX) + 1)));
+    }
+
+    #[test]
+    fn offset_saver_progress() {
+        let map: &[u8] = &[];
+
+        let mut reports = vec![];
+        let (offset, buf) = OffsetSaver::new(map).try_save_with_progress(&nested(), |progress| {
+            reports.push(progress)
+        }).unwrap();
+        assert_eq!(offset, NESTED_OFFSET);
+        assert_eq!(buf, NESTED_BYTES);
+
+        assert_eq!(reports, &[
+            SaveProgress { bytes_written: 1, blobs_saved: 1 },
+            SaveProgress { bytes_written: 9, blobs_saved: 2 },
+            SaveProgress { bytes_written: 17, blobs_saved: 3 },
+            SaveProgress { bytes_written: 25, blobs_saved: 4 },
+        ]);
+        assert!(reports.windows(2).all(|w| w[0].bytes_written < w[1].bytes_written));
+    }
+
+    #[test]
+    fn offset_saver_serialized_size() {
+        let map: &[u8] = &[];
+
+        let size = serialized_size::<Offset, _>(&nested()).unwrap();
+        assert_eq!(size, NESTED_BYTES.len() as u64);
+
+        let size = serialized_size::<Offset32, _>(&nested()).unwrap();
+        let (_, buf) = OffsetSaver::<_, Offset32>::with_offset_type(map).try_save(&nested()).unwrap();
+        assert_eq!(size, buf.len() as u64);
+
+        // Clean pointers are followed, as they are when saving.
+        let map: &[u8] = &buf;
+        let clean: Bag<u8, KeyMut<[u8]>> = unsafe { Bag::from_raw_parts(KeyMut::Key(Key::from_offset(Offset::new(0), map)), ()) };
+        let dirty = KeyMut::<[u8]>::alloc(clean);
+        let size = serialized_size::<Offset, _>(&dirty).unwrap();
+        let (_, buf) = OffsetSaver::new(map).try_save(&dirty).unwrap();
+        assert_eq!(size, buf.len() as u64);
+    }
+
+    #[test]
+    fn offset_saver_dedup() {
+        let map: &[u8] = &[];
+
+        let mut saver = OffsetSaver::with_dedup(map);
+        let a = BlobSaver::save_blob_with::<u16, _>(&mut saver, (), |dst| 0x1234u16.encode_bytes(dst)).into_ok();
+        let b = BlobSaver::save_blob_with::<u8, _>(&mut saver, (), |dst| 42u8.encode_bytes(dst)).into_ok();
+        let c = BlobSaver::save_blob_with::<u16, _>(&mut saver, (), |dst| 0x1234u16.encode_bytes(dst)).into_ok();
+        assert_eq!(a, 0);
+        assert_eq!(b, 2);
+        assert_eq!(c, a);
+        assert_eq!(saver.dst, &[0x34, 0x12, 42]);
+
+        // A prefix of an existing blob is a different blob.
+        let d = BlobSaver::save_blob_with::<u8, _>(&mut saver, (), |dst| 0x34u8.encode_bytes(dst)).into_ok();
+        assert_eq!(d, 3);
+        assert_eq!(saver.dst, &[0x34, 0x12, 42, 0x34]);
+
+        // No duplicates, so the output is identical to the non-deduplicating saver.
+        let saver = OffsetSaver::with_dedup(map);
+        let (offset, buf) = saver.try_save(&nested()).unwrap();
+        assert_eq!(offset, NESTED_OFFSET);
+        assert_eq!(buf, NESTED_BYTES);
     }
 }
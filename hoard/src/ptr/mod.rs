@@ -2,7 +2,7 @@
 
 use std::fmt;
 
-use crate::blob::Blob;
+use crate::blob::{Blob, BlobDyn, Bytes};
 use crate::pointee::Pointee;
 use crate::validate::MaybeValid;
 use crate::owned::{Take, IntoOwned, Ref, RefOwn};
@@ -10,11 +10,14 @@ use crate::bag::Bag;
 use crate::load::LoadRef;
 
 pub mod heap;
-pub use self::heap::Heap;
+pub use self::heap::{Heap, HeapAlloc};
 
 pub mod key;
 pub use self::key::Key;
 
+pub mod cache;
+pub use self::cache::LoadCache;
+
 //pub mod cow;
 //pub use self::cow::Cow;
 
@@ -74,6 +77,26 @@ pub trait Ptr : Sized {
     }
 }
 
+/// An allocator, for pointers that can't be allocated with `Ptr::alloc`.
+pub trait Alloc {
+    type Ptr : Ptr;
+
+    fn alloc<T: ?Sized + Pointee>(&mut self, src: impl Take<T>) -> Bag<T, Self::Ptr>;
+
+    /// Allocates a slice of `len` items from an iterator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the iterator doesn't yield exactly `len` items.
+    fn alloc_slice<T, I>(&mut self, len: usize, iter: I) -> Bag<[T], Self::Ptr>
+        where I: IntoIterator<Item = T>
+    {
+        let items: Vec<T> = iter.into_iter().take(len.saturating_add(1)).collect();
+        assert_eq!(items.len(), len, "iterator yielded the wrong number of items");
+        self.alloc(items)
+    }
+}
+
 /// Needs no deallocation; data available.
 pub trait PtrClean : Copy {
     type Zone : Zone;
@@ -134,6 +157,27 @@ impl<P: PtrBlob> PtrClean for P {
 impl PtrBlob for ! {}
 impl PtrBlob for () {}
 
+/// A clean pointer that can read the blob it points to, without loading it.
+pub trait GetBlob : PtrClean {
+    type Error : 'static + std::error::Error + Send;
+
+    /// Calls `f` with the blob's bytes, and the zone they belong to.
+    fn get_blob_with<T: ?Sized, F, R>(&self, metadata: T::Metadata, f: F) -> Result<R, Self::Error>
+        where T: BlobDyn,
+              F: FnOnce(Bytes<'_, T>, &Self::Zone) -> R;
+}
+
+impl GetBlob for ! {
+    type Error = !;
+
+    fn get_blob_with<T: ?Sized, F, R>(&self, _metadata: T::Metadata, _f: F) -> Result<R, !>
+        where T: BlobDyn,
+              F: FnOnce(Bytes<'_, T>, &()) -> R
+    {
+        match *self {}
+    }
+}
+
 pub trait TryGet : Ptr {
     type Error;
 
@@ -173,6 +217,24 @@ pub trait Get : TryGet {
     {
         self.take_then(metadata, |src| T::into_owned(src.trust()).into())
     }
+
+    /// Gets multiple values at once.
+    ///
+    /// The results are returned in the same order as `ptrs`. Implementations are free to resolve
+    /// the pointers in whatever order is most efficient for the zone.
+    ///
+    /// # Safety
+    ///
+    /// As with `get`, each metadata must be valid for the pointer it's paired with.
+    #[track_caller]
+    unsafe fn get_many<'a, T: ?Sized>(ptrs: &[(&'a Self, T::Metadata)]) -> Vec<MaybeValid<Ref<'a, T>>>
+        where T: LoadRef,
+              Self::Zone: AsZone<T::Zone>
+    {
+        ptrs.iter()
+            .map(|(ptr, metadata)| ptr.get::<T>(*metadata))
+            .collect()
+    }
 }
 
 pub trait TryGetMut : TryGet {
@@ -24,6 +24,18 @@ impl<T> MaybeValid<T> {
     pub fn trust(self) -> T {
         self.0
     }
+
+    /// Maps the wrapped value with a function.
+    ///
+    /// The result is still wrapped: mapping a value doesn't make it any more trustworthy.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> MaybeValid<U> {
+        MaybeValid(f(self.0))
+    }
+
+    /// Maps the wrapped value with a fallible function.
+    pub fn and_then<U, E, F: FnOnce(T) -> Result<U, E>>(self, f: F) -> Result<MaybeValid<U>, E> {
+        f(self.0).map(MaybeValid)
+    }
 }
 
 impl<T: ?Sized> MaybeValid<T> {
@@ -57,3 +69,26 @@ impl<'a, T: ?Sized + IntoOwned> From<MaybeValid<&'a T>> for MaybeValid<Ref<'a, T
         Self(Ref::Borrowed(r.trust()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryFrom;
+
+    #[test]
+    fn map() {
+        let v = MaybeValid::new(42u8);
+        let v: MaybeValid<u16> = v.map(|x| u16::from(x) << 8);
+        assert_eq!(v.trust(), 0x2a00);
+    }
+
+    #[test]
+    fn and_then() {
+        let v = MaybeValid::new(42u8).and_then(u16::try_from).unwrap();
+        assert_eq!(v.trust(), 42u16);
+
+        let r = MaybeValid::new(0x1234u16).and_then(u8::try_from);
+        assert!(r.is_err());
+    }
+}
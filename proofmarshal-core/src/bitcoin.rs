@@ -0,0 +1,98 @@
+//! Bitcoin support.
+
+use std::convert::TryFrom;
+
+use hoard::blob::{Blob, Bytes, BytesUninit};
+use hoard::primitive::Primitive;
+
+use crate::impl_commit;
+use crate::commit::sha256::{Sha256Digest, sha256d};
+
+/// A Bitcoin block header.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BlockHeader {
+    pub version: u32,
+    pub prev_block: [u8; 32],
+    pub merkle_root: [u8; 32],
+    pub time: u32,
+    pub bits: u32,
+    pub nonce: u32,
+}
+
+impl BlockHeader {
+    /// Returns the block hash: the double-SHA256 of the serialized header.
+    ///
+    /// Note that the digest is in byte order, the reverse of how block hashes are usually
+    /// displayed.
+    pub fn block_hash(&self) -> Sha256Digest {
+        sha256d(&self.to_blob_bytes())
+    }
+}
+
+impl Primitive for BlockHeader {
+    const BLOB_SIZE: usize = 80;
+    type DecodeBytesError = !;
+
+    fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
+        let mut buf = [0u8; 80];
+        buf[0 .. 4].copy_from_slice(&self.version.to_le_bytes());
+        buf[4 .. 36].copy_from_slice(&self.prev_block);
+        buf[36 .. 68].copy_from_slice(&self.merkle_root);
+        buf[68 .. 72].copy_from_slice(&self.time.to_le_bytes());
+        buf[72 .. 76].copy_from_slice(&self.bits.to_le_bytes());
+        buf[76 .. 80].copy_from_slice(&self.nonce.to_le_bytes());
+        dst.write_bytes(&buf)
+    }
+
+    fn decode_blob_bytes(src: Bytes<'_, Self>) -> Result<Self, Self::DecodeBytesError> {
+        let u32_at = |i: usize| u32::from_le_bytes(<[u8; 4]>::try_from(&src[i .. i + 4]).unwrap());
+
+        Ok(Self {
+            version: u32_at(0),
+            prev_block: <[u8; 32]>::try_from(&src[4 .. 36]).unwrap(),
+            merkle_root: <[u8; 32]>::try_from(&src[36 .. 68]).unwrap(),
+            time: u32_at(68),
+            bits: u32_at(72),
+            nonce: u32_at(76),
+        })
+    }
+}
+
+impl_commit! {
+    BlockHeader,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use hex_literal::hex;
+
+    fn genesis() -> BlockHeader {
+        BlockHeader {
+            version: 1,
+            prev_block: [0; 32],
+            merkle_root: hex!("3ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a"),
+            time: 1231006505,
+            bits: 0x1d00ffff,
+            nonce: 2083236893,
+        }
+    }
+
+    #[test]
+    fn genesis_block_hash() {
+        let header = genesis();
+        assert_eq!(header.to_blob_bytes(), &hex!("
+            01000000
+            0000000000000000000000000000000000000000000000000000000000000000
+            3ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a
+            29ab5f49 ffff001d 1dac2b7c
+        ")[..]);
+
+        let bytes = Bytes::<BlockHeader>::try_from(&header.to_blob_bytes()[..]).unwrap();
+        assert_eq!(BlockHeader::decode_blob_bytes(bytes).into_ok(), header);
+
+        assert_eq!(header.block_hash().as_bytes(),
+                   &hex!("6fe28c0ab6f1b372c1a6a246ae63f74f931e8365e15a089c68d6190000000000"));
+    }
+}
@@ -14,7 +14,7 @@ use hoard::primitive::Primitive;
 use hoard::blob::{Blob, BlobDyn, Bytes, BytesUninit};
 use hoard::load::{MaybeValid, Load, LoadRef};
 use hoard::save::{Save, SavePoll, Saver};
-use hoard::ptr::{AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
+use hoard::ptr::{Alloc, AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
 use hoard::pointee::Pointee;
 use hoard::owned::{IntoOwned, Take, RefOwn, Ref};
 use hoard::bag::Bag;
@@ -58,6 +58,11 @@ impl<T, P: Ptr, D: Digest> Leaf<T, P, D> {
     {
         Self::new_unchecked(None, P::alloc(value))
     }
+
+    /// Creates a new `Leaf`, with the value allocated by `alloc`.
+    pub fn new_in(value: T, alloc: &mut impl Alloc<Ptr = P>) -> Self {
+        Self::new_unchecked(None, alloc.alloc(value))
+    }
 }
 
 impl<T, P: Ptr, D: Digest> Leaf<T, P, D> {
@@ -91,8 +96,20 @@ impl<T, P: Ptr, D: Digest> Leaf<T, P, D> {
         }
     }
 
+    /// Returns the digest of the `T` value, calculating and caching it if necessary.
+    pub fn digest(&self) -> D
+        where T: Commit
+    {
+        self.value_commit().digest()
+    }
+
+    /// Returns the digest of the `T` value, if cached.
+    pub fn try_digest(&self) -> Option<D> {
+        self.raw.digest()
+    }
+
     /// Returns a hash commit to the `T` value, re-hashing if necessary.
-    fn value_commit(&self) -> HashCommit<T::Commitment, D>
+    pub(crate) fn value_commit(&self) -> HashCommit<T::Commitment, D>
         where T: Commit
     {
         self.try_value_commit()
@@ -148,6 +165,15 @@ where T: Load,
                .trust()
         }
     }
+
+    /// Applies a function to the value, returning a new `Leaf`.
+    ///
+    /// The digest of the new leaf is calculated lazily.
+    pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> Leaf<U, P, D>
+        where P: GetMut + Default
+    {
+        Leaf::new(f(self.take()))
+    }
 }
 
 impl<T, P: Ptr, D: Digest> Leaf<T, P, D> {
@@ -357,6 +383,17 @@ mod tests {
         assert_eq!(leaf_n.value_commit(), HashCommit::new(&43u8));
     }
 
+    #[test]
+    fn digest() {
+        let leaf = Leaf::<u64, Heap>::new(0x1234_5678_9abc_def0);
+        assert_eq!(leaf.try_digest(), None);
+
+        let digest = leaf.digest();
+        assert_eq!(digest, HashCommit::new(&0x1234_5678_9abc_def0_u64).digest());
+        assert_eq!(leaf.try_digest(), Some(digest));
+        assert_eq!(leaf.digest(), digest);
+    }
+
     #[test]
     fn to_commitment() {
         let n = 42u8;
@@ -366,4 +403,13 @@ mod tests {
 
         assert_eq!(n_hash_commit.digest(), leaf_hash_commit.digest())
     }
+
+    #[test]
+    fn map() {
+        let leaf = Leaf::<u8, Heap>::new(42u8);
+        let leaf: Leaf<u16, Heap> = leaf.map(|n| n as u16 * 1000);
+        assert_eq!(*leaf.get(), 42000u16);
+        assert!(leaf.try_value_commit().is_none());
+        assert_eq!(leaf.value_commit(), HashCommit::new(&42000u16));
+    }
 }
@@ -73,11 +73,56 @@ impl Length {
         Self(1 << height.get())
     }
 
+    /// Creates a `Length` from a count of leaves.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` doesn't fit in a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use proofmarshal_core::collections::length::Length;
+    /// assert_eq!(Length::from_leaf_count(0), Length::ZERO);
+    /// assert_eq!(Length::from_leaf_count(7), Length(0b111));
+    /// ```
+    #[track_caller]
+    pub fn from_leaf_count(n: u64) -> Self {
+        Self(usize::try_from(n).expect("leaf count overflows usize"))
+    }
+
     /// Returns the value as a primitive type.
     pub const fn get(self) -> usize {
         self.0
     }
 
+    /// Returns true if this length is a single perfect tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use proofmarshal_core::collections::length::Length;
+    /// assert!(!Length(0).is_perfect());
+    /// assert!(Length(0b100).is_perfect());
+    /// assert!(!Length(0b101).is_perfect());
+    /// ```
+    pub const fn is_perfect(self) -> bool {
+        self.0.count_ones() == 1
+    }
+
+    /// Returns the number of peaks, the perfect trees making up this length.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use proofmarshal_core::collections::length::Length;
+    /// assert_eq!(Length(0).peak_count(), 0);
+    /// assert_eq!(Length(0b1011).peak_count(), 3);
+    /// ```
+    pub const fn peak_count(self) -> u32 {
+        self.0.count_ones()
+    }
+
     /// Checked addition.
     ///
     /// # Examples
@@ -132,6 +177,43 @@ impl Length {
             _ => true,
         }
     }
+
+    /// Returns the heights of the peaks, from highest to lowest.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use proofmarshal_core::collections::length::Length;
+    /// let heights: Vec<u8> = Length(0b101).peak_heights().map(|h| h.get()).collect();
+    /// assert_eq!(heights, &[2, 0]);
+    /// ```
+    pub fn peak_heights(self) -> impl Iterator<Item = Height> {
+        (0 ..= Height::MAX).rev()
+                           .map(|n| Height::new(n).unwrap())
+                           .filter(move |height| self.contains(*height))
+    }
+
+    /// Returns a value that displays the peak heights, e.g. `[h2, h0]`.
+    pub fn display_peaks(self) -> DisplayPeaks {
+        DisplayPeaks(self)
+    }
+}
+
+/// Displays the peak heights of a `Length`; see `Length::display_peaks`.
+#[derive(Clone, Copy, Debug)]
+pub struct DisplayPeaks(Length);
+
+impl fmt::Display for DisplayPeaks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("[")?;
+        for (i, height) in self.0.peak_heights().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "h{}", height)?;
+        }
+        f.write_str("]")
+    }
 }
 
 impl ToLength for Length {
@@ -318,6 +400,16 @@ impl NonZeroLength {
     pub fn contains(self, height: impl Into<Height>) -> bool {
         Length::from(self).contains(height)
     }
+
+    /// Returns the heights of the peaks, from highest to lowest.
+    pub fn peak_heights(self) -> impl Iterator<Item = Height> {
+        Length::from(self).peak_heights()
+    }
+
+    /// Returns a value that displays the peak heights, e.g. `[h2, h0]`.
+    pub fn display_peaks(self) -> DisplayPeaks {
+        Length::from(self).display_peaks()
+    }
 }
 
 impl InnerLength {
@@ -684,6 +776,7 @@ impl Primitive for NonZeroLength {
     type DecodeBytesError = NonZeroLengthError<u64>;
 
     const BLOB_SIZE: usize = 8;
+    const NONZERO_NICHE: bool = true;
 
     fn encode_blob_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
         let n: u64 = self.0.get().try_into().unwrap();
@@ -903,4 +996,44 @@ mod test {
         assert_eq!(InnerLength::MAX & NonZeroLength::MAX,
                    Length::MAX);
     }
+
+    #[test]
+    fn from_leaf_count() {
+        let l = Length::from_leaf_count(0);
+        assert_eq!(l, 0);
+        assert!(!l.is_perfect());
+        assert_eq!(l.peak_count(), 0);
+
+        for i in 0 .. 64 {
+            let l = Length::from_leaf_count(1 << i);
+            assert_eq!(l, 1 << i);
+            assert!(l.is_perfect());
+            assert_eq!(l.peak_count(), 1);
+        }
+
+        let l = Length::from_leaf_count(7);
+        assert!(!l.is_perfect());
+        assert_eq!(l.peak_count(), 3);
+
+        let l = Length::from_leaf_count(255);
+        assert!(!l.is_perfect());
+        assert_eq!(l.peak_count(), 8);
+    }
+
+    #[test]
+    fn peak_heights() {
+        let heights: Vec<Height> = Length::from_leaf_count(5).peak_heights().collect();
+        assert_eq!(heights, &[2, 0]);
+        assert_eq!(Length::from_leaf_count(5).display_peaks().to_string(), "[h2, h0]");
+
+        assert_eq!(Length(0).peak_heights().count(), 0);
+        assert_eq!(Length(0).display_peaks().to_string(), "[]");
+
+        assert_eq!(Length::MAX.peak_heights().count(), 64);
+        assert_eq!(Length::MAX.peak_heights().next(), Some(Height::new(63).unwrap()));
+
+        let len = NonZeroLength::new(0b1010).unwrap();
+        assert_eq!(len.peak_heights().collect::<Vec<Height>>(), &[3, 1]);
+        assert_eq!(len.display_peaks().to_string(), "[h3, h1]");
+    }
 }
@@ -4,6 +4,7 @@ use std::borrow::{Borrow, BorrowMut};
 use std::cmp;
 use std::convert::TryFrom;
 use std::error;
+use std::iter::FromIterator;
 use std::marker::PhantomData;
 use std::mem::{self, ManuallyDrop, MaybeUninit};
 use std::ops::DerefMut;
@@ -11,17 +12,17 @@ use std::ptr;
 
 use thiserror::Error;
 
-use hoard::blob::{Blob, BlobDyn, Bytes, BytesUninit};
+use hoard::blob::{Blob, BlobDyn, Bytes, BytesUninit, NonZeroPaddingError};
 use hoard::bag::Bag;
 use hoard::primitive::Primitive;
 use hoard::owned::{IntoOwned, Take, Ref, RefOwn};
 use hoard::pointee::Pointee;
-use hoard::ptr::{Get, GetMut, Ptr, PtrBlob, Zone, AsZone};
+use hoard::ptr::{Get, GetMut, Heap, Ptr, PtrBlob, Zone, AsZone};
 use hoard::load::{Load, LoadRef, MaybeValid};
 use hoard::save::{Save, SavePoll, Saver};
 
 use crate::commit::{
-    Commit, Digest,
+    Commit, Digest, Hasher,
     sha256::Sha256Digest,
 };
 use crate::collections::leaf::Leaf;
@@ -37,15 +38,34 @@ pub struct MMR<T, P: Ptr, D: Digest = Sha256Digest> {
     peaks: Option<PeakTree<T, P, D>>,
 }
 
+/// How the peaks are combined into a single digest by `MMR::root_digest_with`.
+///
+/// Different MMR specifications bag their peaks differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaggingOrder {
+    /// The tree of peaks used by `MMR::root_digest`.
+    Tree,
+
+    /// Folds the peaks largest first: `H(H(a || b) || c)`.
+    LeftToRight,
+
+    /// Folds the peaks smallest first: `H(a || H(b || c))`.
+    RightToLeft,
+}
+
+impl Default for BaggingOrder {
+    fn default() -> Self {
+        BaggingOrder::Tree
+    }
+}
+
 impl<T: Commit, P: Ptr, D: Digest> Commit for MMR<T, P, D> {
     type Commitment = MMR<T::Commitment, (), D>;
 
     fn to_commitment(&self) -> Self::Commitment {
-        /*
         MMR {
-            peaks: self.peaks.to_commitment(),
+            peaks: self.peaks.as_ref().map(Commit::to_commitment),
         }
-        */ todo!()
     }
 }
 
@@ -77,6 +97,70 @@ impl<T, P: Ptr, D: Digest> MMR<T, P, D> {
     pub fn peaks_mut(&mut self) -> Option<&mut PeakTree<T, P, D>> {
         self.peaks.as_mut()
     }
+
+    /// Removes all items, dropping the peaks.
+    pub fn clear(&mut self) {
+        self.peaks = None;
+    }
+}
+
+impl<T: Commit, P: Ptr, D: Digest> MMR<T, P, D> {
+    /// Returns the root digest of the `MMR`.
+    ///
+    /// This is the hash of the encoded commitment of the `MMR`:
+    ///
+    /// ```text
+    /// H(peaks || len)
+    /// ```
+    ///
+    /// ...where `len` is the number of items as a little-endian `u64`, and `peaks` is the digest
+    /// of the tree of peaks, or all zeros if the `MMR` is empty.
+    ///
+    /// The peaks are the perfect trees corresponding to each bit set in `len`, largest first. If
+    /// there is only one peak, `peaks` is simply the digest of that perfect tree. Otherwise the
+    /// peaks are split in two, with the left side getting the largest power-of-two number of
+    /// peaks that leaves at least one peak for the right side (see `InnerLength::split`), and
+    /// `peaks` is:
+    ///
+    /// ```text
+    /// H(left || right || left_len + right_len)
+    /// ```
+    ///
+    /// ...again with the length as a little-endian `u64`. Digests less than or equal to the size
+    /// of `D` are used verbatim rather than hashed, as with `HashCommit`.
+    pub fn root_digest(&self) -> D {
+        let mut hasher = D::Hasher::default();
+        hasher.hash_blob(&self.to_commitment());
+        hasher.finish()
+    }
+
+    /// Proves the length of the `MMR` against its root digest.
+    ///
+    /// See `verify_length`.
+    pub fn prove_length(&self) -> (Length, LengthProof<D>) {
+        let peaks = self.peaks.as_ref().map(|peaks| {
+            peaks.to_commitment().into_raw_node().commitment_digest()
+        }).unwrap_or_default();
+
+        (self.len(), LengthProof { peaks })
+    }
+}
+
+/// Proof of the length of an `MMR`.
+///
+/// The root digest commits to the length directly, so the proof is simply the digest of the peaks
+/// (all zeros for an empty `MMR`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LengthProof<D> {
+    peaks: D,
+}
+
+/// Verifies that `len` is the length of the `MMR` with root digest `root`.
+pub fn verify_length<D: Digest>(root: D, len: Length, proof: &LengthProof<D>) -> bool {
+    let mut hasher = D::Hasher::default();
+    hasher.hash_bytes(proof.peaks.as_ref());
+    hasher.hash_bytes(&(len.get() as u64).to_le_bytes());
+    hasher.finish() == root
 }
 
 impl<T, P: Ptr, D: Digest> MMR<T, P, D>
@@ -113,6 +197,17 @@ where T: Load,
         }
     }
 
+    /// Makes a deep copy of the `MMR`, with every node in memory.
+    ///
+    /// See `PerfectTreeDyn::deep_clone` for the cost.
+    pub fn deep_clone(&self) -> Self
+        where T: Clone, P: Get + Default
+    {
+        MMR {
+            peaks: self.peaks.as_ref().map(|peaks| peaks.deep_clone()),
+        }
+    }
+
     pub fn get(&self, idx: usize) -> Option<Ref<T>>
         where P: Get,
     {
@@ -165,6 +260,103 @@ where T: Load,
             None => None,
         }
     }
+
+    /// Returns the root digest of the `MMR`, with the peaks bagged in the given order.
+    ///
+    /// As with `root_digest`, this is `H(peaks || len)`; only how `peaks` is computed differs. With
+    /// a single peak all orders produce the same digest.
+    pub fn root_digest_with(&self, order: BaggingOrder) -> D
+        where T: Commit, P: Get
+    {
+        let peaks = match order {
+            BaggingOrder::Tree => return self.root_digest(),
+            BaggingOrder::LeftToRight => self.peak_digests().into_iter().reduce(bag_peaks),
+            BaggingOrder::RightToLeft => self.peak_digests().into_iter().rev().reduce(|acc, peak| bag_peaks(peak, acc)),
+        }.unwrap_or_default();
+
+        let mut hasher = D::Hasher::default();
+        hasher.hash_bytes(peaks.as_ref());
+        hasher.hash_bytes(&(self.len().get() as u64).to_le_bytes());
+        hasher.finish()
+    }
+
+    /// Returns the digest of every peak, largest first.
+    fn peak_digests(&self) -> Vec<D>
+        where T: Commit, P: Get
+    {
+        let mut digests = vec![];
+        if let Some(peaks) = &self.peaks {
+            for height in peaks.len().peak_heights() {
+                let peak = peaks.get(height).expect("peak heights to exist");
+                digests.push(peak.to_commitment().into_raw_node().commitment_digest());
+            }
+        }
+        digests
+    }
+
+    /// Finds the index of the first value whose hash commitment is `digest`.
+    ///
+    /// This is O(n), and meant for convenience: use `get_leaf` when the index is known.
+    pub fn find_by_commitment(&self, digest: D) -> Option<usize>
+        where T: Commit, P: Get
+    {
+        let peaks = self.peaks.as_ref()?;
+
+        let mut offset = 0;
+        for height in peaks.len().peak_heights() {
+            let peak = peaks.get(height).expect("peak heights to exist");
+            if let Some(idx) = peak.find_by_commitment(digest) {
+                return Some(offset + idx);
+            }
+            offset += usize::from(peak.len());
+        }
+        None
+    }
+}
+
+/// Collects values into an in-memory `MMR`.
+///
+/// # Panics
+///
+/// Panics if the iterator yields more than `Length::MAX` values.
+impl<T, D: Digest> FromIterator<T> for MMR<T, Heap, D>
+where T: Load,
+      <Heap as Ptr>::Zone: AsZone<T::Zone>,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut mmr = Self::new();
+        for value in iter {
+            mmr.try_push(value).ok().expect("MMR length overflow");
+        }
+        mmr
+    }
+}
+
+/// Compares two `MMR`s value by value, regardless of pointer type.
+///
+/// This loads every value in both `MMR`s.
+impl<T, P: Ptr, Q: Ptr, D: Digest> PartialEq<MMR<T, Q, D>> for MMR<T, P, D>
+where T: PartialEq + Load,
+      P: Get, Q: Get,
+      P::Zone: AsZone<T::Zone>,
+      Q::Zone: AsZone<T::Zone>,
+{
+    fn eq(&self, other: &MMR<T, Q, D>) -> bool {
+        self.len() == other.len()
+            && (0 .. self.len().get()).all(|idx| *self.get(idx).unwrap() == *other.get(idx).unwrap())
+    }
+}
+
+/// Bags two peak digests together, as `H(left || right)`.
+///
+/// There's no explicit domain separation from `perfecttree::combine`, which appends a height byte,
+/// or from `MMR::root_digest`, which appends a length: the three only differ in the length of the
+/// hashed input.
+fn bag_peaks<D: Digest>(left: D, right: D) -> D {
+    let mut hasher = D::Hasher::default();
+    hasher.hash_bytes(left.as_ref());
+    hasher.hash_bytes(right.as_ref());
+    hasher.finish()
 }
 
 /// Determines the height of the peak containing a given index, as well as the index within that
@@ -255,7 +447,6 @@ fn idx_to_containing_height(len: NonZeroLength, idx: usize) -> Option<(Height, u
 pub enum DecodeMMRBytesError<Peaks: error::Error, Len: error::Error> {
     Peaks(Peaks),
     Len(Len),
-    NonZeroPadding,
 }
 
 impl<T, P: Ptr, D: Digest> Blob for MMR<T, P, D>
@@ -267,16 +458,10 @@ where T: 'static,
                                                 <Length as Blob>::DecodeBytesError>;
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
-        if let Some(peaks) = &self.peaks {
-            dst.write_struct()
-               .write_field(peaks)
-               .done()
-        } else {
-            dst.write_struct()
-               .write_padding(<PeakTree<T, P, D> as Blob>::SIZE - <Length as Blob>::SIZE)
-               .write_field(&Length::ZERO)
-               .done()
-        }
+        // An empty MMR is all zeros, which also happens to be a zero length.
+        dst.write_struct()
+           .write_field(&self.peaks)
+           .done()
     }
 
     fn decode_bytes(src: Bytes<'_, Self>) -> Result<MaybeValid<Self>, Self::DecodeBytesError> {
@@ -285,16 +470,27 @@ where T: 'static,
         let peaks = match fields.trust_field::<PeakTree<T, P, D>>() {
             Ok(peaks) => Ok(Some(peaks)),
             Err(DecodePeakTreeBytesError::Raw(raw)) => Err(DecodeMMRBytesError::Peaks(DecodePeakTreeDynBytesError(raw))),
-            Err(DecodePeakTreeBytesError::NonZeroLength(err)) if err.0 == 0 => {
-                // FIXME: check for non-zero padding
-                Ok(None)
-            },
+            Err(DecodePeakTreeBytesError::NonZeroLength(err)) if err.0 == 0 => Ok(None),
             Err(DecodePeakTreeBytesError::NonZeroLength(_err)) => Err(DecodeMMRBytesError::Len(LengthError)),
         }?;
 
         fields.assert_done();
         Ok(Self { peaks }.into())
     }
+
+    fn validate_padding(src: Bytes<'_, Self>) -> Result<(), NonZeroPaddingError> {
+        // An empty MMR is padding followed by a zero length.
+        let padding_len = <PeakTree<T, P, D> as Blob>::SIZE - <Length as Blob>::SIZE;
+        if src[padding_len ..].iter().all(|b| *b == 0) {
+            let mut fields = src.struct_fields();
+            fields.validate_padding(padding_len)?;
+            fields.validate_padding(<Length as Blob>::SIZE)?;
+            fields.assert_done();
+            Ok(())
+        } else {
+            <PeakTree<T, P, D> as Blob>::validate_padding(Bytes::try_from(&src[..]).unwrap())
+        }
+    }
 }
 
 impl<T, P: Ptr, D: Digest> Load for MMR<T, P, D>
@@ -438,6 +634,238 @@ mod tests {
     }
 
 
+    #[test]
+    fn clear() {
+        use dropcheck::{DropCheck, DropToken};
+
+        #[derive(Debug)]
+        struct Token(DropToken);
+
+        // Only needed for MMR's bounds: values in a heap MMR are never loaded.
+        impl Load for Token {
+            type Blob = u8;
+            type PtrClean = !;
+            type Zone = ();
+
+            fn load_maybe_valid(_: MaybeValid<&u8>, _: &()) -> MaybeValid<Self> {
+                unreachable!()
+            }
+        }
+
+        let dropcheck = DropCheck::new();
+
+        let mut mmr = MMR::<Token, Heap>::new();
+        for _ in 0 .. 11 {
+            mmr.try_push(Token(dropcheck.token())).unwrap();
+        }
+        assert!(dropcheck.none_dropped());
+
+        mmr.clear();
+        assert_eq!(mmr.len(), 0);
+        assert!(mmr.peaks().is_none());
+        assert!(mmr.get(0).is_none());
+        assert!(dropcheck.all_dropped());
+
+        let (token, state) = dropcheck.pair();
+        mmr.try_push(Token(token)).unwrap();
+        assert_eq!(mmr.len(), 1);
+        assert!(mmr.get(0).is_some());
+        assert!(mmr.get(1).is_none());
+        assert!(state.is_not_dropped());
+
+        drop(mmr);
+        assert!(state.is_dropped());
+    }
+
+    #[test]
+    fn decode_bytes_strict() {
+        use hoard::blob::DecodeStrictError;
+        use hoard::ptr::key::Offset;
+
+        let mut buf = MMR::<u8, Offset>::new().to_blob_bytes();
+        let src = Bytes::<MMR<u8, Offset>>::try_from(&buf[..]).unwrap();
+        assert_eq!(<MMR<u8, Offset> as Blob>::decode_bytes_strict(src).unwrap().trust().len(), 0);
+
+        buf[0] = 1;
+        let src = Bytes::<MMR<u8, Offset>>::try_from(&buf[..]).unwrap();
+        assert_eq!(<MMR<u8, Offset> as Blob>::decode_bytes(src).unwrap().trust().len(), 0);
+        assert!(matches!(<MMR<u8, Offset> as Blob>::decode_bytes_strict(src),
+                         Err(DecodeStrictError::NonZeroPadding)));
+    }
+
+    #[test]
+    fn to_commitment() {
+        use hex_literal::hex;
+        use crate::commit::HashCommit;
+
+        let mmr = MMR::<u8, Heap>::new();
+        let commitment = mmr.to_commitment();
+        assert_eq!(commitment.len(), 0);
+        assert!(commitment.peaks().is_none());
+
+        let mut mmr = MMR::<u8, Heap>::new();
+        mmr.try_push(42).unwrap();
+        mmr.try_push(43).unwrap();
+        mmr.try_push(44).unwrap();
+
+        let commitment = mmr.to_commitment();
+        assert_eq!(commitment.len(), 3);
+        assert_eq!(HashCommit::<_>::new(&mmr).digest().as_ref(),
+                   &hex!("68b0ebb19d3de4bb21a1c4619443d7559f143a2deb9de68fde8567fc4b5d6398")[..]);
+    }
+
+    #[test]
+    fn root_digest() {
+        use hex_literal::hex;
+        use crate::commit::sha256::Sha256Hasher;
+
+        let mut mmr = MMR::<u8, Heap>::new();
+        assert_eq!(mmr.root_digest().as_ref(),
+                   &hex!("2c34ce1df23b838c5abf2a7f6437cca3d3067ed509ff25f11df6b11b582b51eb")[..]);
+
+        mmr.try_push(42).unwrap();
+        assert_eq!(mmr.root_digest().as_ref(),
+                   &hex!("72eb5681cea95cf00a81624b096a50b7193dd85b7864ab9ede5b6ef8e6b1ebd8")[..]);
+
+        // One peak, the pair (42, 43).
+        mmr.try_push(43).unwrap();
+        assert_eq!(mmr.root_digest().as_ref(),
+                   &hex!("433259d22406a59553c4aa9a979e2161bd53ef00b093a92e94d2587ca0140149")[..]);
+
+        // Two peaks, the pair (42, 43) and the leaf 44.
+        mmr.try_push(44).unwrap();
+        assert_eq!(mmr.root_digest().as_ref(),
+                   &hex!("68b0ebb19d3de4bb21a1c4619443d7559f143a2deb9de68fde8567fc4b5d6398")[..]);
+
+        for i in 45 .. 52 {
+            mmr.try_push(i).unwrap();
+
+            let peaks = mmr.peaks().unwrap().to_commitment().into_raw_node();
+            let mut hasher = Sha256Hasher::default();
+            hasher.hash_bytes(peaks.digest().unwrap().as_ref());
+            hasher.hash_bytes(&(mmr.len().get() as u64).to_le_bytes());
+            assert_eq!(mmr.root_digest(), hasher.finish());
+        }
+    }
+
+    #[test]
+    fn root_digest_with() {
+        use crate::commit::sha256::Sha256Hasher;
+
+        fn h(parts: &[&[u8]]) -> Sha256Digest {
+            let mut hasher = Sha256Hasher::default();
+            for part in parts {
+                hasher.hash_bytes(part);
+            }
+            hasher.finish()
+        }
+
+        let mmr: MMR<u8, Heap> = (0 .. 4).collect();
+        assert_eq!(mmr.root_digest_with(BaggingOrder::LeftToRight), mmr.root_digest());
+        assert_eq!(mmr.root_digest_with(BaggingOrder::RightToLeft), mmr.root_digest());
+
+        let mmr: MMR<u8, Heap> = (0 .. 7).collect();
+        assert_eq!(mmr.root_digest_with(BaggingOrder::default()), mmr.root_digest());
+
+        let peaks = mmr.peaks().unwrap();
+        let digests: Vec<Sha256Digest> = mmr.len().peak_heights().map(|height| {
+            peaks.get(height).unwrap().to_commitment().into_raw_node().digest().unwrap()
+        }).collect();
+        let (a, b, c) = (digests[0], digests[1], digests[2]);
+        let len = 7u64.to_le_bytes();
+
+        let left_to_right = mmr.root_digest_with(BaggingOrder::LeftToRight);
+        assert_eq!(left_to_right,
+                   h(&[h(&[h(&[a.as_ref(), b.as_ref()]).as_ref(), c.as_ref()]).as_ref(), &len]));
+
+        let right_to_left = mmr.root_digest_with(BaggingOrder::RightToLeft);
+        assert_eq!(right_to_left,
+                   h(&[h(&[a.as_ref(), h(&[b.as_ref(), c.as_ref()]).as_ref()]).as_ref(), &len]));
+
+        assert_ne!(left_to_right, right_to_left);
+        assert_ne!(left_to_right, mmr.root_digest());
+    }
+
+    #[test]
+    fn prove_length() {
+        let mut mmr = MMR::<u8, Heap>::new();
+
+        let (len, proof) = mmr.prove_length();
+        assert_eq!(len, 0);
+        assert!(verify_length(mmr.root_digest(), len, &proof));
+        assert!(!verify_length(mmr.root_digest(), Length(1), &proof));
+
+        for i in 0 .. 42 {
+            mmr.try_push(i).unwrap();
+        }
+
+        let root = mmr.root_digest();
+        let (len, proof) = mmr.prove_length();
+        assert_eq!(len, 42);
+        assert!(verify_length(root, len, &proof));
+        assert!(!verify_length(root, Length(43), &proof));
+    }
+
+    #[test]
+    fn commits_eq() {
+        let mut pushed = MMR::<u8, Heap>::new();
+        for i in 0 .. 8 {
+            pushed.try_push(i).unwrap();
+        }
+
+        let mut trees: Vec<PerfectTree<u8, Heap>> = (0 .. 8).map(PerfectTree::new_leaf).collect();
+        while trees.len() > 1 {
+            let mut children = trees.into_iter();
+            trees = vec![];
+            while let (Some(left), Some(right)) = (children.next(), children.next()) {
+                trees.push(PerfectTree::try_join(left, right).unwrap());
+            }
+        }
+        let joined = MMR::<u8, Heap> {
+            peaks: Some(trees.pop().unwrap().into()),
+        };
+        assert_eq!(joined.len(), 8);
+        assert!(pushed.commits_eq(&joined));
+        assert!(joined.commits_eq(&pushed));
+
+        pushed.try_push(8).unwrap();
+        assert!(!pushed.commits_eq(&joined));
+    }
+
+    #[test]
+    fn peaks_get_mut() {
+        use crate::collections::perfecttree::{Kind, PerfectTreeDyn};
+
+        fn leftmost_mut(tree: &mut PerfectTreeDyn<u8, Heap>) -> &mut u8 {
+            match tree.kind_mut() {
+                Kind::Leaf(leaf) => leaf.get_mut(),
+                Kind::Tip(tip) => leftmost_mut(tip.get_pair_mut().left_mut()),
+            }
+        }
+
+        let mut mmr = MMR::<u8, Heap>::new();
+        for i in 0 .. 7 {
+            mmr.try_push(i).unwrap();
+        }
+        let root = mmr.root_digest();
+
+        let peaks = mmr.peaks_mut().unwrap();
+        assert!(peaks.get_mut(Height::new(3).unwrap()).is_none());
+
+        let peak = peaks.get_mut(Height::new(1).unwrap()).unwrap();
+        assert_eq!(peak.height(), 1);
+        *leftmost_mut(peak) = 100;
+
+        assert_eq!(mmr.get(4).unwrap(), &100);
+        assert_ne!(mmr.root_digest(), root);
+
+        let mut expected = MMR::<u8, Heap>::new();
+        for i in &[0, 1, 2, 3, 100, 5, 6] {
+            expected.try_push(*i).unwrap();
+        }
+        assert_eq!(mmr.root_digest(), expected.root_digest());
+    }
+
     #[test]
     fn heap_get() {
         let mut mmr = MMR::<u32,Heap>::new();
@@ -480,20 +908,59 @@ mod tests {
 
         mmr.try_push(43).unwrap();
         t(&mmr, 82, &[
-            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 250, 74, 74, 79, 147, 58, 207, 22, 6, 235, 253, 179, 116, 242, 232, 247, 23, 215, 116, 250, 221, 195, 13, 100, 238, 143, 228, 191, 182, 184, 237, 154, 2, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0
+            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 144, 166, 31, 71, 11, 60, 188, 148, 181, 232, 180, 157, 94, 143, 94, 219, 159, 97, 255, 207, 94, 51, 109, 15, 214, 181, 46, 53, 44, 173, 99, 39, 2, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0
         ]);
 
         mmr.try_push(44).unwrap();
         t(&mmr, 163, &[
-            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 44, 250, 74, 74, 79, 147, 58, 207, 22, 6, 235, 253, 179, 116, 242, 232, 247, 23, 215, 116, 250, 221, 195, 13, 100, 238, 143, 228, 191, 182, 184, 237, 154, 2, 0, 0, 0, 0, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 82, 0, 0, 0, 0, 0, 0, 0, 138, 125, 6, 251, 217, 239, 106, 103, 2, 109, 242, 95, 16, 143, 157, 213, 3, 198, 231, 236, 185, 164, 188, 23, 157, 97, 151, 110, 147, 47, 235, 190, 83, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0
+            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 44, 144, 166, 31, 71, 11, 60, 188, 148, 181, 232, 180, 157, 94, 143, 94, 219, 159, 97, 255, 207, 94, 51, 109, 15, 214, 181, 46, 53, 44, 173, 99, 39, 2, 0, 0, 0, 0, 0, 0, 0, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 82, 0, 0, 0, 0, 0, 0, 0, 79, 244, 148, 138, 21, 130, 90, 31, 66, 195, 72, 156, 157, 182, 186, 199, 103, 97, 64, 21, 78, 226, 235, 101, 151, 149, 238, 180, 34, 196, 220, 178, 83, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0
         ]);
 
         mmr.try_push(45).unwrap();
         t(&mmr, 244, &[
-            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 44, 45, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 82, 0, 0, 0, 0, 0, 0, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 83, 0, 0, 0, 0, 0, 0, 0, 250, 74, 74, 79, 147, 58, 207, 22, 6, 235, 253, 179, 116, 242, 232, 247, 23, 215, 116, 250, 221, 195, 13, 100, 238, 143, 228, 191, 182, 184, 237, 154, 2, 0, 0, 0, 0, 0, 0, 0, 164, 244, 200, 22, 32, 194, 203, 252, 168, 252, 91, 233, 88, 222, 38, 115, 86, 146, 146, 86, 1, 152, 121, 190, 253, 18, 48, 244, 155, 44, 213, 159, 84, 0, 0, 0, 0, 0, 0, 0, 79, 64, 109, 199, 230, 180, 200, 195, 102, 189, 161, 26, 69, 87, 71, 112, 6, 153, 86, 6, 222, 176, 115, 211, 60, 242, 180, 8, 244, 74, 221, 110, 164, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0
+            42, 43, 42, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 43, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 44, 45, 44, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 82, 0, 0, 0, 0, 0, 0, 0, 45, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 83, 0, 0, 0, 0, 0, 0, 0, 144, 166, 31, 71, 11, 60, 188, 148, 181, 232, 180, 157, 94, 143, 94, 219, 159, 97, 255, 207, 94, 51, 109, 15, 214, 181, 46, 53, 44, 173, 99, 39, 2, 0, 0, 0, 0, 0, 0, 0, 220, 1, 219, 141, 100, 174, 45, 50, 214, 233, 235, 191, 8, 118, 196, 169, 38, 254, 207, 209, 219, 86, 113, 206, 72, 228, 248, 220, 33, 57, 173, 217, 84, 0, 0, 0, 0, 0, 0, 0, 20, 75, 47, 209, 233, 253, 191, 7, 230, 222, 76, 218, 210, 119, 120, 56, 41, 47, 164, 120, 254, 115, 83, 89, 21, 183, 154, 109, 166, 192, 223, 15, 164, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0
         ]);
     }
 
+    #[test]
+    fn deep_clone() {
+        let mut mmr = MMR::<u8, Heap>::new();
+        for i in 0 .. 11 {
+            mmr.try_push(i).unwrap();
+        }
+
+        let mut clone = mmr.deep_clone();
+        assert_eq!(clone.len(), 11);
+        assert_eq!(clone.root_digest(), mmr.root_digest());
+
+        clone.try_push(11).unwrap();
+        assert_eq!(clone.len(), 12);
+        assert_eq!(mmr.len(), 11);
+        for i in 0u8 .. 11 {
+            assert_eq!(clone.get(i as usize).unwrap(), &i);
+            assert_eq!(mmr.get(i as usize).unwrap(), &i);
+        }
+    }
+
+    #[test]
+    fn save_then_get_from_offset() {
+        let mut mmr = MMR::<u8, Heap>::new();
+        for i in 0 .. 10 {
+            mmr.try_push(i).unwrap();
+        }
+
+        let (offset, buf) = OffsetSaver::new(&[][..]).try_save(&mmr).unwrap();
+
+        let key = Key::from_offset(offset, &buf[..]);
+        let bag: Bag<MMR<u8, Key<[u8]>>, _> = unsafe { Bag::from_raw_parts(key, ()) };
+
+        let mmr = bag.get();
+        assert_eq!(mmr.len(), 10);
+        for i in 0u8 .. 10 {
+            assert_eq!(mmr.get(i as usize).unwrap(), &i);
+        }
+    }
+
     #[test]
     fn save_then_get() {
         let mut mmr = MMR::<u16, Heap>::new();
@@ -547,4 +1014,91 @@ mod tests {
             assert_eq!(bag.get().get(i as usize).unwrap(), &i);
         }
     }
+
+    #[test]
+    fn eq() {
+        let mmr: MMR<u16, Heap> = (0 .. 42).collect();
+        assert!(mmr == (0 .. 42).collect::<MMR<u16, Heap>>());
+        assert!(mmr != (0 .. 41).collect::<MMR<u16, Heap>>());
+        assert!(mmr != (1 .. 43).collect::<MMR<u16, Heap>>());
+
+        let saver = OffsetSaver::new(&[][..]);
+        let (offset, buf) = saver.try_save(&mmr).unwrap();
+
+        let map: &[u8] = &buf;
+        let key = Key::<[u8]>::from_blob(offset, &map);
+        let bag: Bag<MMR<u16, Key<[u8]>>, _> = unsafe { Bag::from_raw_parts(key, ()) };
+        assert!(*bag.get() == mmr);
+    }
+
+    #[test]
+    fn save_append() {
+        let mut mmr = MMR::<u16, Heap>::new();
+        for i in 0 .. 4 {
+            mmr.try_push(i).unwrap();
+        }
+
+        let (offset, buf) = OffsetSaver::new(&[][..]).try_save(&mmr).unwrap();
+
+        let map: &[u8] = &buf;
+        let key = Key::<[u8]>::from_blob(offset, &map);
+        let mut bag: Bag<MMR<u16, KeyMut<[u8]>>, _> = unsafe { Bag::from_raw_parts(KeyMut::Key(key), ()) };
+        let mmr = bag.get_mut();
+        mmr.try_push(4).unwrap();
+
+        // Only the new nodes are written; the existing peak is reused by offset.
+        let (_, resaved) = OffsetSaver::new(map).try_save(mmr).unwrap();
+        let (offset, appended) = OffsetSaver::append(map).try_save(mmr).unwrap();
+        assert!(appended.len() < resaved.len());
+        assert!(offset.get() >= buf.len() as u64);
+
+        let mut full = buf.clone();
+        full.extend_from_slice(&appended);
+
+        let map: &[u8] = &full;
+        let key = Key::<[u8]>::from_blob(offset, &map);
+        let bag: Bag<MMR<u16, Key<[u8]>>, _> = unsafe { Bag::from_raw_parts(key, ()) };
+        assert_eq!(bag.get().len(), 5);
+        for i in 0 .. 5 {
+            assert_eq!(bag.get().get(i as usize).unwrap(), &i);
+        }
+    }
+
+    #[test]
+    fn find_by_commitment() {
+        use crate::commit::HashCommit;
+
+        let mut mmr = MMR::<u32, Heap>::new();
+        assert_eq!(mmr.find_by_commitment(HashCommit::new(&0u32).digest()), None);
+
+        for i in 0 .. 11 {
+            mmr.try_push(i * 100).unwrap();
+        }
+
+        for i in 0 .. 11 {
+            let digest = HashCommit::new(&(i * 100u32)).digest();
+            assert_eq!(mmr.find_by_commitment(digest), Some(i as usize));
+        }
+
+        assert_eq!(mmr.find_by_commitment(HashCommit::new(&42u32).digest()), None);
+    }
+
+    #[test]
+    fn from_iter() {
+        let mmr: MMR<u32, Heap> = (0 .. 64).collect();
+        assert_eq!(mmr.len(), 64);
+        for &i in &[0, 1, 31, 32, 63] {
+            assert_eq!(*mmr.get(i as usize).unwrap(), i);
+        }
+        assert!(mmr.get(64).is_none());
+
+        let mut pushed = MMR::<u32, Heap>::new();
+        for i in 0 .. 64 {
+            pushed.try_push(i).unwrap();
+        }
+        assert_eq!(mmr.root_digest(), pushed.root_digest());
+
+        let empty: MMR<u32, Heap> = std::iter::empty().collect();
+        assert_eq!(empty.len(), 0);
+    }
 }
@@ -13,7 +13,7 @@ use hoard::primitive::Primitive;
 use hoard::blob::{Blob, BlobDyn, Bytes, BytesUninit};
 use hoard::load::{MaybeValid, Load, LoadRef};
 use hoard::save::{Save, SavePoll, SaveRef, SaveRefPoll, Saver};
-use hoard::ptr::{AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
+use hoard::ptr::{Alloc, AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
 use hoard::pointee::Pointee;
 use hoard::owned::{IntoOwned, Take, RefOwn, Ref};
 use hoard::bag::Bag;
@@ -174,6 +174,37 @@ where T: Load
             Kind::Inner(inner) => inner.get(height),
         }
     }
+
+    /// Gets a mutable reference to the peak with the given height, if it exists.
+    ///
+    /// The digests along the path to the peak are cleared, so they'll be recalculated.
+    pub fn get_mut(&mut self, height: Height) -> Option<&mut PerfectTreeDyn<T, P, D>>
+        where P: GetMut
+    {
+        match self.kind_mut() {
+            Kind::Peak(peak) if peak.height() == height => Some(peak),
+            Kind::Peak(_) => None,
+            Kind::Inner(inner) => inner.get_mut(height),
+        }
+    }
+
+    /// Makes a deep copy of the peaks, with every node in memory.
+    ///
+    /// Each peak is copied with `PerfectTreeDyn::deep_clone`, and costs the same.
+    pub fn deep_clone(&self) -> PeakTree<T, P, D>
+        where T: Clone,
+              P: Get + Default,
+              P::Zone: AsZone<T::Zone>,
+    {
+        match self.kind() {
+            Kind::Peak(peak) => peak.deep_clone().into(),
+            Kind::Inner(inner) => {
+                let pair = inner.get_pair();
+                let pair = Pair::new(pair.left().deep_clone(), pair.right().deep_clone());
+                Inner::new(pair).into()
+            },
+        }
+    }
 }
 
 impl<T, P: Ptr, D: Digest> PeakTreeDyn<T, P, D> {
@@ -223,6 +254,17 @@ impl<T, P: Ptr, D: Digest> Inner<T, P, D> {
         Self::new_unchecked(None, P::alloc(pair))
     }
 
+    pub fn try_join_peaks_in(left: PerfectTree<T, P, D>, right: PerfectTree<T, P, D>, alloc: &mut impl Alloc<Ptr = P>)
+        -> Result<Self, (PerfectTree<T, P, D>, PerfectTree<T, P, D>)>
+    {
+        let pair = Pair::try_join_peaks(left, right)?;
+        Ok(Self::new_in(pair, alloc))
+    }
+
+    pub fn new_in(pair: Pair<T, P, D>, alloc: &mut impl Alloc<Ptr = P>) -> Self {
+        Self::new_unchecked(None, alloc.alloc(pair))
+    }
+
     pub fn new_unchecked(digest: Option<D>, pair: Bag<PairDyn<T, P, D>, P>) -> Self {
         let (ptr, len) = pair.into_raw_parts();
         let raw = raw::Node::new(digest, ptr);
@@ -319,6 +361,16 @@ where T: Load
         }
     }
 
+    pub fn get_mut(&mut self, height: Height) -> Option<&mut PerfectTreeDyn<T, P, D>>
+        where P: GetMut
+    {
+        if self.len().contains(height) {
+            self.get_pair_mut().get_mut(height)
+        } else {
+            None
+        }
+    }
+
     pub fn get_pair(&self) -> Ref<PairDyn<T, P, D>>
         where P: Get
     {
@@ -463,6 +515,21 @@ where T: Load
             None
         }
     }
+
+    pub fn get_mut(&mut self, height: Height) -> Option<&mut PerfectTreeDyn<T, P, D>>
+        where P: GetMut
+    {
+        if self.len().contains(height) {
+            let (left, right) = self.split_mut();
+            if left.len().contains(height) {
+                left.get_mut(height)
+            } else {
+                right.get_mut(height)
+            }
+        } else {
+            None
+        }
+    }
 }
 
 impl<T, P: Ptr, D: Digest> PairDyn<T, P, D> {
@@ -769,6 +836,10 @@ where T: 'static,
       P: Blob,
 {
     const SIZE: usize = <raw::Node<T, P, D> as Blob>::SIZE + <NonZeroLength as Blob>::SIZE;
+
+    // The length is never zero.
+    const NONZERO_NICHE: bool = true;
+
     type DecodeBytesError = DecodePeakTreeBytesError<<raw::Node<T, P, D> as Blob>::DecodeBytesError, <NonZeroLength as Blob>::DecodeBytesError>;
 
     fn encode_bytes<'a>(&self, dst: BytesUninit<'a, Self>) -> Bytes<'a, Self> {
@@ -15,13 +15,13 @@ use hoard::primitive::Primitive;
 use hoard::blob::{Blob, BlobDyn, Bytes, BytesUninit};
 use hoard::load::{MaybeValid, Load, LoadRef};
 use hoard::save::{Save, SavePoll, SaveRef, SaveRefPoll, Saver};
-use hoard::ptr::{AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
+use hoard::ptr::{Alloc, AsZone, Zone, Get, GetMut, Ptr, PtrClean, PtrBlob};
 use hoard::pointee::Pointee;
 use hoard::owned::{IntoOwned, Take, RefOwn, Ref};
 use hoard::bag::Bag;
 
 use crate::commit::{
-    Commit, Digest,
+    Commit, Digest, Hasher,
     HashCommit,
     sha256::Sha256Digest,
 };
@@ -32,6 +32,28 @@ use super::length::*;
 use super::raw;
 use super::leaf::{Leaf, LeafSavePoll};
 
+/// Combines the digests of the two children of a `Tip`, returning the digest of the tip's `Pair`.
+///
+/// Exactly:
+///
+/// ```text
+/// H(left || right || height)
+/// ```
+///
+/// ...where `H` is `D`'s hash function, `left` and `right` are the raw digest bytes, and `height`
+/// is the height of the tip as a single byte.
+///
+/// That height suffix is the only domain separation. There is no prefix or tag: the input is just
+/// the two digests followed by one byte. The height is needed because it's part of what a `Pair`
+/// commits to, so this takes three arguments rather than two.
+pub fn combine<D: Digest>(left: D, right: D, height: NonZeroHeight) -> D {
+    let mut hasher = D::Hasher::default();
+    hasher.hash_blob(&left);
+    hasher.hash_blob(&right);
+    hasher.hash_blob(&height);
+    hasher.finish()
+}
+
 /// An inner node within a `Tip`.
 #[repr(C)]
 pub struct Pair<T, P: Ptr, D: Digest = Sha256Digest> {
@@ -107,6 +129,17 @@ impl<T, P: Ptr, D: Digest> PerfectTree<T, P, D> {
     {
         Self::from(Leaf::new(value))
     }
+
+    pub fn try_join_in(left: PerfectTree<T, P, D>, right: PerfectTree<T, P, D>, alloc: &mut impl Alloc<Ptr = P>)
+        -> Result<Self, (PerfectTree<T, P, D>, PerfectTree<T, P, D>)>
+    {
+        let tip = Tip::try_join_in(left, right, alloc)?;
+        Ok(Self::from(tip))
+    }
+
+    pub fn new_leaf_in(value: T, alloc: &mut impl Alloc<Ptr = P>) -> Self {
+        Self::from(Leaf::new_in(value, alloc))
+    }
 }
 
 impl<T, P: Ptr, D: Digest> From<Leaf<T, P, D>> for PerfectTree<T, P, D> {
@@ -188,6 +221,113 @@ where T: Load,
             Kind::Tip(tip) => tip.get_leaf(idx),
         }
     }
+
+    /// Makes a deep copy of the tree, with every node in memory.
+    ///
+    /// This is expensive: the entire tree is loaded, and every value cloned.
+    pub fn deep_clone(&self) -> PerfectTree<T, P, D>
+        where T: Clone, P: Get + Default
+    {
+        match self.kind() {
+            Kind::Leaf(leaf) => PerfectTree::new_leaf(T::clone(&leaf.get())),
+            Kind::Tip(tip) => {
+                let pair = tip.get_pair();
+                PerfectTree::try_join(pair.left().deep_clone(), pair.right().deep_clone())
+                            .ok().expect("heights to match")
+            },
+        }
+    }
+
+    /// Walks the tree in pre-order, returning the height and digest of every tip and leaf.
+    ///
+    /// Nodes are visited lazily, and digests are calculated as needed, and cached.
+    pub fn walk(&self) -> impl Iterator<Item = (Height, D)> + '_
+        where T: Commit, P: Get
+    {
+        Walk {
+            stack: vec![Ref::Borrowed(self)],
+        }
+    }
+
+    fn height_and_digest(&self) -> (Height, D)
+        where T: Commit, P: Get
+    {
+        match self.kind() {
+            Kind::Leaf(leaf) => (Height::ZERO, leaf.value_commit().digest()),
+            Kind::Tip(tip) => (tip.height().into(), tip.pair_commit().digest()),
+        }
+    }
+
+    /// Finds the index of the first leaf whose value hash commits to `digest`.
+    ///
+    /// This is O(n): leaves are checked one by one, with their digests calculated as needed.
+    pub fn find_by_commitment(&self, digest: D) -> Option<usize>
+        where T: Commit, P: Get
+    {
+        match self.kind() {
+            Kind::Leaf(leaf) if leaf.value_commit().digest() == digest => Some(0),
+            Kind::Leaf(_) => None,
+            Kind::Tip(tip) => {
+                let pair = tip.get_pair();
+                pair.left().find_by_commitment(digest).or_else(|| {
+                    let left_len = usize::from(pair.left().len());
+                    pair.right().find_by_commitment(digest)
+                                .map(|idx| left_len + idx)
+                })
+            },
+        }
+    }
+}
+
+/// Pre-order iterator returned by `PerfectTreeDyn::walk`.
+///
+/// Nodes still to be visited are kept on an explicit stack, with the left child on top. Subtrees
+/// that had to be loaded are owned by the stack until they're visited.
+struct Walk<'a, T, P: Ptr, D: Digest> {
+    stack: Vec<Ref<'a, PerfectTreeDyn<T, P, D>>>,
+}
+
+impl<'a, T, P: Ptr, D: Digest> Walk<'a, T, P, D> {
+    fn push_pair(&mut self, pair: Ref<'a, PairDyn<T, P, D>>) {
+        match pair {
+            Ref::Borrowed(pair) => {
+                self.stack.push(Ref::Borrowed(pair.right()));
+                self.stack.push(Ref::Borrowed(pair.left()));
+            },
+            Ref::Owned(pair) => {
+                let (left, right) = pair.into_split();
+                self.stack.push(Ref::Owned(right));
+                self.stack.push(Ref::Owned(left));
+            },
+        }
+    }
+}
+
+impl<'a, T, P: Ptr, D: Digest> Iterator for Walk<'a, T, P, D>
+where T: Load + Commit,
+      P: Get,
+      P::Zone: AsZone<T::Zone>,
+{
+    type Item = (Height, D);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.stack.pop()? {
+            Ref::Borrowed(node) => {
+                let item = node.height_and_digest();
+                if let Kind::Tip(tip) = node.kind() {
+                    self.push_pair(tip.get_pair());
+                }
+                Some(item)
+            },
+            Ref::Owned(node) => {
+                let item = node.height_and_digest();
+                if let Kind::Tip(tip) = node.into_kind() {
+                    self.push_pair(Ref::Owned(tip.into_get_pair()));
+                }
+                Some(item)
+            },
+        }
+    }
 }
 
 impl<T, P: Ptr, D: Digest> PerfectTreeDyn<T, P, D> {
@@ -253,6 +393,17 @@ impl<T, P: Ptr, D: Digest> Tip<T, P, D> {
         Self::new_unchecked(None, P::alloc(pair))
     }
 
+    pub fn try_join_in(left: PerfectTree<T, P, D>, right: PerfectTree<T, P, D>, alloc: &mut impl Alloc<Ptr = P>)
+        -> Result<Self, (PerfectTree<T, P, D>, PerfectTree<T, P, D>)>
+    {
+        let pair = Pair::try_join(left, right)?;
+        Ok(Self::new_in(pair, alloc))
+    }
+
+    pub fn new_in(pair: Pair<T, P, D>, alloc: &mut impl Alloc<Ptr = P>) -> Self {
+        Self::new_unchecked(None, alloc.alloc(pair))
+    }
+
     pub fn new_unchecked(digest: Option<D>, pair: Bag<PairDyn<T, P, D>, P>) -> Self {
         let (ptr, height) = pair.into_raw_parts();
         let raw = raw::Node::new(digest, ptr);
@@ -338,9 +489,13 @@ impl<T, P: Ptr, D: Digest> TipDyn<T, P, D> {
     {
         let pair = self.try_get_dirty_pair()
                        .ok().expect("digest missing yet tip ptr clean");
-        let hash_commit = HashCommit::new(pair);
-        self.raw.set_digest(hash_commit.digest());
-        hash_commit
+
+        let left = pair.left().to_commitment().into_raw_node().commitment_digest();
+        let right = pair.right().to_commitment().into_raw_node().commitment_digest();
+
+        let digest = combine(left, right, self.height());
+        self.raw.set_digest(digest);
+        HashCommit::from_digest(digest)
     }
 
     /// Gets a commitment to the pair owned by this tip, if already available.
@@ -978,7 +1133,7 @@ impl<T, P: Ptr, D: Digest> fmt::Debug for PerfectTree<T, P, D>
 where T: fmt::Debug, P: fmt::Debug, D: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.kind().fmt(f)
+        (**self).fmt(f)
     }
 }
 
@@ -986,7 +1141,33 @@ impl<T, P: Ptr, D: Digest> fmt::Debug for PerfectTreeDyn<T, P, D>
 where T: fmt::Debug, P: fmt::Debug, D: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.kind().fmt(f)
+        match self.kind() {
+            Kind::Tip(tip) if tip.try_get_dirty_pair().is_err() => self.debug_shallow().fmt(f),
+            kind => kind.fmt(f),
+        }
+    }
+}
+
+impl<T, P: Ptr, D: Digest> PerfectTreeDyn<T, P, D> {
+    /// Returns a `Debug` implementation that prints the height and cached digest, without
+    /// descending into the tree.
+    pub fn debug_shallow(&self) -> impl fmt::Debug + '_
+        where D: fmt::Debug
+    {
+        struct DebugShallow<'a, T, P: Ptr, D: Digest>(&'a PerfectTreeDyn<T, P, D>);
+
+        impl<T, P: Ptr, D: Digest> fmt::Debug for DebugShallow<'_, T, P, D>
+        where D: fmt::Debug
+        {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct("PerfectTree")
+                    .field("height", &self.0.height())
+                    .field("digest", &self.0.raw.digest())
+                    .finish()
+            }
+        }
+
+        DebugShallow(self)
     }
 }
 
@@ -1056,7 +1237,7 @@ where T: Commit,
 
     fn to_commitment(&self) -> Self::Commitment {
         let left = self.left().to_commitment();
-        let right = self.left().to_commitment();
+        let right = self.right().to_commitment();
 
         Pair::try_join(left, right).ok().unwrap()
     }
@@ -1420,8 +1601,9 @@ mod tests {
         ptr::{
             Heap,
             key::{
-                Map,
-                offset::OffsetSaver,
+                Key, Map,
+                map::Slice32,
+                offset::{IoSaver, Offset32, OffsetSaver},
             },
         },
     };
@@ -1447,12 +1629,89 @@ mod tests {
             1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
             1, 0, 0, 0, 0, 0, 0, 0,
 
-            44, 204, 28, 235, 16, 65, 239, 191, 88, 43, 77, 236, 197, 8, 120, 35, 76, 45, 42, 219, 120, 41, 255, 162, 60, 146, 237, 100, 16, 92, 138, 56,
+            146, 245, 11, 56, 0, 112, 1, 38, 17, 4, 15, 207, 40, 126, 207, 148, 51, 140, 180, 214, 185, 96, 155, 19, 181, 40, 39, 191, 210, 34, 244, 149,
             2, 0, 0, 0, 0, 0, 0, 0,
             1 // height
         ]);
     }
 
+    #[test]
+    fn io_saver() {
+        let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
+        let leaf1 = PerfectTree::<u8, Heap>::new_leaf(1u8);
+        let tree0 = PerfectTree::try_join(leaf0, leaf1).unwrap();
+
+        let (expected_offset, expected_buf) = OffsetSaver::new(&[][..]).try_save(&tree0).unwrap();
+
+        let saver = IoSaver::new(&[][..], vec![]);
+        let (offset, buf) = saver.try_save(&tree0).unwrap();
+        assert_eq!(offset, expected_offset);
+        assert_eq!(buf, expected_buf);
+    }
+
+    #[test]
+    fn offset32_saver() {
+        let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
+        let leaf1 = PerfectTree::<u8, Heap>::new_leaf(1u8);
+        let tree0 = PerfectTree::try_join(leaf0, leaf1).unwrap();
+
+        let (_, wide_buf) = OffsetSaver::new(&[][..]).try_save(&tree0).unwrap();
+
+        let saver = OffsetSaver::<_, Offset32>::with_offset_type(&[][..]);
+        let (offset, buf) = saver.try_save(&tree0).unwrap();
+        assert_eq!(offset, 74);
+        assert_eq!(buf.len(), wide_buf.len() - 3 * 4);
+
+        let map = Slice32::new(&buf);
+        let bag: Bag<PerfectTree<u8, Key<Slice32>>, _> = unsafe {
+            Bag::from_raw_parts(Key::from_blob(offset, &map), ())
+        };
+        let tree = bag.get();
+        assert_eq!(tree.get(0).unwrap(), &0);
+        assert_eq!(tree.get(1).unwrap(), &1);
+        assert_eq!(tree.get(2), None);
+    }
+
+    #[test]
+    fn debug_clean_is_shallow() {
+        let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
+        let leaf1 = PerfectTree::<u8, Heap>::new_leaf(1u8);
+        let tree0 = PerfectTree::try_join(leaf0, leaf1).unwrap();
+        assert!(format!("{:?}", tree0).contains("Leaf"));
+
+        let saver = OffsetSaver::<_, Offset32>::with_offset_type(&[][..]);
+        let (offset, buf) = saver.try_save(&tree0).unwrap();
+
+        let map = Slice32::new(&buf);
+        let bag: Bag<PerfectTree<u8, Key<Slice32>>, _> = unsafe {
+            Bag::from_raw_parts(Key::from_blob(offset, &map), ())
+        };
+        let tree = bag.get();
+        let debug = format!("{:?}", &*tree);
+        assert_eq!(debug, format!("{:?}", tree.debug_shallow()));
+        assert!(!debug.contains("Leaf"));
+        assert!(debug.len() < 200, "{}", debug);
+    }
+
+    #[test]
+    fn drop_frees_heap() {
+        let initial = Heap::live_allocations();
+
+        let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
+        let leaf1 = PerfectTree::<u8, Heap>::new_leaf(1u8);
+        let tree0 = PerfectTree::try_join(leaf0, leaf1).unwrap();
+
+        let leaf2 = PerfectTree::<u8, Heap>::new_leaf(2u8);
+        let leaf3 = PerfectTree::<u8, Heap>::new_leaf(3u8);
+        let tree1 = PerfectTree::try_join(leaf2, leaf3).unwrap();
+
+        let tree = PerfectTree::try_join(tree0, tree1).unwrap();
+        assert!(Heap::live_allocations() > initial);
+
+        drop(tree);
+        assert_eq!(Heap::live_allocations(), initial);
+    }
+
     #[test]
     fn test_get() {
         let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
@@ -1464,6 +1723,90 @@ mod tests {
         assert_eq!(tree0.get(usize::MAX), None);
     }
 
+    #[test]
+    fn combine() {
+        use std::num::NonZeroU8;
+
+        let d0 = HashCommit::<u8>::new(&0u8).digest();
+        let d1 = HashCommit::<u8>::new(&1u8).digest();
+        let height = NonZeroHeight::new(NonZeroU8::new(1).unwrap()).unwrap();
+
+        assert_ne!(super::combine(d0, d1, height), super::combine(d1, d0, height));
+
+        let leaf0 = PerfectTree::<u8, Heap>::new_leaf(0u8);
+        let leaf1 = PerfectTree::<u8, Heap>::new_leaf(1u8);
+        let tree0 = PerfectTree::try_join(leaf0, leaf1).unwrap();
+
+        match tree0.kind() {
+            Kind::Tip(tip) => {
+                assert_eq!(tip.height(), height);
+                assert_eq!(tip.pair_commit().digest(), super::combine(d0, d1, height));
+
+                // The Pair commitment itself must hash to the same digest.
+                let pair = tip.get_pair();
+                assert_eq!(HashCommit::<Pair<u8, (), Sha256Digest>>::new(&*pair).digest(),
+                           tip.pair_commit().digest());
+            },
+            Kind::Leaf(_) => panic!(),
+        }
+    }
+
+    #[test]
+    fn deep_clone() {
+        fn leftmost_mut(tree: &mut PerfectTreeDyn<u8, Heap>) -> &mut u8 {
+            match tree.kind_mut() {
+                Kind::Leaf(leaf) => leaf.get_mut(),
+                Kind::Tip(tip) => leftmost_mut(tip.get_pair_mut().left_mut()),
+            }
+        }
+
+        let tree01 = PerfectTree::try_join(PerfectTree::<u8, Heap>::new_leaf(0), PerfectTree::new_leaf(1)).unwrap();
+        let tree23 = PerfectTree::try_join(PerfectTree::new_leaf(2), PerfectTree::new_leaf(3)).unwrap();
+        let tree = PerfectTree::try_join(tree01, tree23).unwrap();
+
+        let mut clone = tree.deep_clone();
+        assert_eq!(clone.len(), tree.len());
+        for i in 0u8 .. 4 {
+            assert_eq!(clone.get(i as usize).unwrap(), &i);
+        }
+
+        *leftmost_mut(&mut clone) = 100;
+        assert_eq!(clone.get(0).unwrap(), &100);
+        assert_eq!(tree.get(0).unwrap(), &0);
+        for i in 1u8 .. 4 {
+            assert_eq!(clone.get(i as usize).unwrap(), &i);
+            assert_eq!(tree.get(i as usize).unwrap(), &i);
+        }
+    }
+
+    #[test]
+    fn walk() {
+        let leaves = (0u8 .. 4).map(PerfectTree::<u8, Heap>::new_leaf).collect::<Vec<_>>();
+        let mut leaves = leaves.into_iter();
+        let mut join = || PerfectTree::try_join(leaves.next().unwrap(), leaves.next().unwrap()).unwrap();
+        let tree01 = join();
+        let tree23 = join();
+        let tree = PerfectTree::try_join(tree01, tree23).unwrap();
+
+        let nodes: Vec<(Height, Sha256Digest)> = tree.walk().collect();
+        assert_eq!(nodes.len(), 2 * 4 - 1);
+
+        let heights: Vec<u8> = nodes.iter().map(|(height, _)| height.get()).collect();
+        assert_eq!(heights, &[2, 1, 0, 0, 1, 0, 0]);
+
+        match tree.kind() {
+            Kind::Tip(tip) => assert_eq!(nodes[0].1, tip.pair_commit().digest()),
+            Kind::Leaf(_) => panic!(),
+        }
+        assert_eq!(nodes[2].1, HashCommit::<u8>::new(&0).digest());
+        assert_eq!(nodes[3].1, HashCommit::<u8>::new(&1).digest());
+        assert_eq!(nodes[5].1, HashCommit::<u8>::new(&2).digest());
+        assert_eq!(nodes[6].1, HashCommit::<u8>::new(&3).digest());
+
+        let height = NonZeroHeight::try_from(nodes[1].0).unwrap();
+        assert_eq!(nodes[1].1, super::combine(nodes[2].1, nodes[3].1, height));
+    }
+
     #[test]
     fn test_commit() {
         /*
@@ -1478,4 +1821,18 @@ mod tests {
         let _ = tree0.to_verbatim();
         */
     }
+
+    #[test]
+    fn new_in() {
+        use hoard::ptr::HeapAlloc;
+
+        let mut alloc = HeapAlloc;
+        let left = PerfectTree::<u8, Heap>::new_leaf_in(1, &mut alloc);
+        let right = PerfectTree::new_leaf_in(2, &mut alloc);
+        let tree = PerfectTree::try_join_in(left, right, &mut alloc).unwrap();
+        assert_eq!(*tree.get(1).unwrap(), 2);
+
+        let expected = PerfectTree::<u8, Heap>::try_join(PerfectTree::new_leaf(1), PerfectTree::new_leaf(2)).unwrap();
+        assert!(tree.commits_eq(&expected));
+    }
 }
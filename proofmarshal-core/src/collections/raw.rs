@@ -61,6 +61,15 @@ impl<T, P, D: Digest> Node<T, P, D> {
     }
 }
 
+impl<T, D: Digest> Node<T, (), D> {
+    /// Returns the digest of a commitment node.
+    ///
+    /// Commitments are always created with their digest, so unlike `digest` this can't fail.
+    pub fn commitment_digest(&self) -> D {
+        self.digest().expect("commitment digests are always set")
+    }
+}
+
 impl<T, P: Ptr, D: Digest> Node<T, P, D> {
     pub unsafe fn get<U: ?Sized>(&self, metadata: U::Metadata) -> MaybeValid<Ref<U>>
         where U: LoadRef,
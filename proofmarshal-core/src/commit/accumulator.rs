@@ -0,0 +1,74 @@
+//! Unordered set commitments.
+
+use super::{Digest, Sha256Digest};
+
+/// A commitment to a set of digests, where the order of insertion doesn't matter.
+///
+/// The accumulated digest is simply the XOR of every digest added. Adding and removing are thus
+/// the same operation, and adding the same digest twice removes it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AccumulatorDigest<D = Sha256Digest> {
+    digest: D,
+}
+
+impl<D: Digest> AccumulatorDigest<D> {
+    /// Creates a new, empty, accumulator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, digest: D) {
+        self.digest = self.digest.xor(digest);
+    }
+
+    pub fn remove(&mut self, digest: D) {
+        self.digest = self.digest.xor(digest);
+    }
+
+    pub fn digest(&self) -> D {
+        self.digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::commit::sha256d;
+
+    #[test]
+    fn xor() {
+        let a = sha256d(b"a");
+        let b = Sha256Digest::from([0xff; 32]);
+
+        assert_eq!(a.xor(b), b.xor(a));
+        assert_eq!(a.xor(b).xor(b), a);
+        assert_eq!(a.xor(a), Sha256Digest::default());
+    }
+
+    #[test]
+    fn add_remove() {
+        let a = Sha256Digest::from([1; 32]);
+        let b = Sha256Digest::from([2; 32]);
+        let c = Sha256Digest::from([4; 32]);
+
+        let mut acc1 = AccumulatorDigest::new();
+        acc1.add(a);
+        acc1.add(b);
+        acc1.add(c);
+
+        let mut acc2 = AccumulatorDigest::new();
+        acc2.add(c);
+        acc2.add(a);
+        acc2.add(b);
+        assert_eq!(acc1, acc2);
+        assert_eq!(acc1.digest(), Sha256Digest::from([7; 32]));
+
+        let before = acc1;
+        let d = Sha256Digest::from([0x42; 32]);
+        acc1.add(d);
+        assert_ne!(acc1, before);
+        acc1.remove(d);
+        assert_eq!(acc1, before);
+    }
+}
@@ -24,4 +24,15 @@ mod tests {
     #[test]
     fn test() {
     }
+
+    #[test]
+    fn to_commitment() {
+        let items = [(1u8, 0x0302u16), (4u8, 0x0605u16)];
+
+        let mut expected = items[0].to_commitment().to_blob_bytes();
+        expected.extend(items[1].to_commitment().to_blob_bytes());
+
+        assert_eq!(items.to_commitment().to_blob_bytes(), expected);
+        assert_eq!(expected, &[1, 2, 3, 4, 5, 6]);
+    }
 }
@@ -0,0 +1,32 @@
+use super::*;
+
+/// A bag commits to the value it points to, not the pointer itself.
+impl<T: Load + Commit, P: Get> Commit for Bag<T, P>
+where P::Zone: AsZone<T::Zone>
+{
+    type Commitment = T::Commitment;
+
+    fn to_commitment(&self) -> Self::Commitment {
+        self.get().to_commitment()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_commitment() {
+        let bag: Bag<u8, Heap> = Heap::alloc(42u8);
+        assert_eq!(bag.to_commitment(), 42u8);
+
+        // Different allocations of the same value have the same commitment.
+        let a: (u8, Bag<u8, Heap>) = (1, Heap::alloc(42u8));
+        let b: (u8, Bag<u8, Heap>) = (1, Heap::alloc(42u8));
+        assert_eq!(a.to_commitment(), (1, 42));
+        assert_eq!(a.to_commitment(), b.to_commitment());
+
+        let c: (u8, Bag<u8, Heap>) = (1, Heap::alloc(43u8));
+        assert_ne!(a.to_commitment(), c.to_commitment());
+    }
+}
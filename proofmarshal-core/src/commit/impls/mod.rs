@@ -1,5 +1,8 @@
 use super::*;
 
 mod option;
+mod refs;
+mod bag;
 mod arrays;
 mod slices;
+mod tuples;
@@ -0,0 +1,17 @@
+use super::*;
+
+impl<T: ?Sized + Commit> Commit for &T {
+    type Commitment = T::Commitment;
+
+    fn to_commitment(&self) -> Self::Commitment {
+        (**self).to_commitment()
+    }
+
+    fn encode_commitment_bytes<'a>(&self, dst: BytesUninit<'a, Self::Commitment>) -> Bytes<'a, Self::Commitment> {
+        (**self).encode_commitment_bytes(dst)
+    }
+
+    fn hash_commitment_with<H: Hasher>(&self, hasher: H) -> H::Output {
+        (**self).hash_commitment_with(hasher)
+    }
+}
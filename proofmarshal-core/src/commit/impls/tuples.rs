@@ -0,0 +1,64 @@
+use super::*;
+
+macro_rules! impl_tuple {
+    ( $($name:ident,)+ ) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Commit),+ > Commit for ($($name,)+) {
+            type Commitment = ($(<$name as Commit>::Commitment,)+);
+
+            fn to_commitment(&self) -> Self::Commitment {
+                let ($(ref $name,)+) = self;
+                ($($name.to_commitment(),)+)
+            }
+        }
+    }
+}
+
+macro_rules! peel {
+    ($name:ident, $( $rest_name:ident,)* ) => (tuple! { $( $rest_name, )* })
+}
+
+macro_rules! reverse {
+    ([] $($reversed:ident)*) => {
+        impl_tuple!( $( $reversed, )* );
+    };
+    ([$first:ident $($rest:ident)*] $($reversed:tt)*) => {
+        reverse!([$($rest)*] $first $($reversed)*);  // recursion
+    };
+}
+
+macro_rules! tuple {
+    () => ();
+    ( $($name:ident,)+ ) => {
+        reverse!([ $( $name )+ ]);
+        peel! { $($name,)+ }
+    }
+}
+
+tuple! ( T11, T10, T9, T8, T7, T6, T5, T4, T3, T2, T1, T0, );
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_commitment() {
+        #[track_caller]
+        fn t<T: Commit>(value: T, expected: &[u8]) {
+            let actual = value.to_commitment().to_blob_bytes();
+            assert_eq!(actual, expected);
+        }
+
+        t((1u8,), &[1]);
+        t((1u8, 0x0302u16, [4u8, 5, 6, 7]),
+         &[1,
+           2, 3,
+           4, 5, 6, 7,
+         ]);
+        t((true, (), 8u8, Some(9u8)),
+         &[1,
+           8,
+           1, 9,
+         ]);
+    }
+}
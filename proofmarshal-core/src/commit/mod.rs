@@ -17,9 +17,20 @@ mod impls;
 pub mod sha256;
 pub use self::sha256::*;
 
+pub mod accumulator;
+pub use self::accumulator::AccumulatorDigest;
+
 pub trait Digest : Primitive + Default + AsRef<[u8]> + AsMut<[u8]> + Eq
 {
     type Hasher : Default + Hasher<Output = Self>;
+
+    /// Bitwise XOR of two digests.
+    fn xor(mut self, other: Self) -> Self {
+        for (a, b) in self.as_mut().iter_mut().zip(other.as_ref()) {
+            *a ^= b;
+        }
+        self
+    }
 }
 
 pub trait Hasher {
@@ -72,6 +83,14 @@ pub trait Commit {
         hasher.hash_blob(&self.to_commitment());
         hasher.finish()
     }
+
+    /// Returns true if both values have the same commitment.
+    ///
+    /// Unlike `PartialEq`, this ignores how the values are represented, e.g. whether pointers are
+    /// clean or dirty.
+    fn commits_eq(&self, other: &Self) -> bool {
+        self.to_commitment().to_blob_bytes() == other.to_commitment().to_blob_bytes()
+    }
 }
 
 /// Variable-length commitments.
@@ -118,9 +137,10 @@ macro_rules! impl_commit {
 
 impl_commit! {
     !, (),
-    bool,
+    bool, char,
     u8, u16, u32, u64, u128,
     i8, i16, i32, i64, i128,
+    std::time::Duration,
 }
 
 /// A commitment that has been hashed to reduce its length (if necessary).
@@ -189,6 +209,18 @@ impl<T: ?Sized + BlobDyn, D: Digest> HashCommit<T, D> {
             Self::from_digest(digest)
         }
     }
+
+    /// Creates a `HashCommit` for a value whose digest is already known.
+    ///
+    /// The caller asserts that `digest` is what `new` would compute. This is only checked in debug
+    /// builds.
+    #[track_caller]
+    pub fn from_parts<U>(value: &U, digest: D) -> Self
+        where U: ?Sized + CommitRef<CommitmentDyn=T>
+    {
+        debug_assert!(Self::new(value).digest() == digest, "digest doesn't match value");
+        Self::from_digest(digest)
+    }
 }
 
 impl<T: ?Sized, D: Digest> HashCommit<T, D> {
@@ -282,4 +314,32 @@ mod tests {
         t([1u8,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25,26,27,28,29,30,31,32,33],
          &[49, 176, 60, 110, 174, 212, 117, 221, 227, 69, 177, 206, 130, 147, 185, 174, 139, 252, 123, 217, 102, 101, 151, 221, 242, 140, 24, 250, 115, 213, 196, 244])
     }
+
+    #[test]
+    fn from_parts() {
+        let value = [42u8; 33];
+        let digest = HashCommit::<[u8; 33]>::new(&value).digest();
+
+        let commit = HashCommit::<[u8; 33]>::from_parts(&value, digest);
+        assert_eq!(commit, HashCommit::new(&value));
+        assert_eq!(commit.digest(), digest);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "digest doesn't match value")]
+    fn from_parts_mismatch() {
+        HashCommit::<[u8; 33]>::from_parts(&[42u8; 33], Sha256Digest::default());
+    }
+
+    #[test]
+    fn commit_ref() {
+        // An outpoint: txid and output index.
+        let outpoint = (Sha256Digest::from([0xab; 32]), 1u32);
+
+        assert_eq!(HashCommit::<(Sha256Digest, u32)>::new(&&outpoint),
+                   HashCommit::<(Sha256Digest, u32)>::new(&outpoint));
+        assert_eq!(<&(Sha256Digest, u32) as Commit>::to_commitment(&&outpoint).to_blob_bytes(),
+                   outpoint.to_commitment().to_blob_bytes());
+    }
 }
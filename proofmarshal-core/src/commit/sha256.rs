@@ -5,6 +5,8 @@ use std::convert::TryFrom;
 use hoard::blob::{Bytes, BytesUninit};
 use hoard::primitive::Primitive;
 
+use crate::impl_commit;
+
 use super::{Digest, Hasher};
 
 /// A SHA256 digest.
@@ -26,6 +28,32 @@ impl AsMut<[u8]> for Sha256Digest {
     }
 }
 
+impl Sha256Digest {
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    #[inline]
+    pub fn into_bytes(self) -> [u8; 32] {
+        self.0
+    }
+}
+
+impl From<[u8; 32]> for Sha256Digest {
+    #[inline]
+    fn from(digest: [u8; 32]) -> Self {
+        Self(digest)
+    }
+}
+
+impl From<Sha256Digest> for [u8; 32] {
+    #[inline]
+    fn from(digest: Sha256Digest) -> Self {
+        digest.0
+    }
+}
+
 /// A `Hasher` to create SHA256 digests.
 #[derive(Default)]
 pub struct Sha256Hasher(sha2::Sha256);
@@ -53,6 +81,30 @@ impl Hasher for Sha256Hasher {
     }
 }
 
+/// Bitcoin's double-SHA256: `SHA256(SHA256(data))`.
+pub fn sha256d(data: &[u8]) -> Sha256Digest {
+    let mut hasher = Sha256Hasher::default();
+    hasher.hash_bytes(data);
+    let digest = hasher.finish();
+
+    let mut hasher = Sha256Hasher::default();
+    hasher.hash_bytes(&digest.0);
+    hasher.finish()
+}
+
+/// BIP-340 tagged hash: `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> Sha256Digest {
+    let mut hasher = Sha256Hasher::default();
+    hasher.hash_bytes(tag.as_bytes());
+    let tag_digest = hasher.finish();
+
+    let mut hasher = Sha256Hasher::default();
+    hasher.hash_bytes(&tag_digest.0);
+    hasher.hash_bytes(&tag_digest.0);
+    hasher.hash_bytes(data);
+    hasher.finish()
+}
+
 impl Primitive for Sha256Digest {
     const BLOB_SIZE: usize = 32;
     type DecodeBytesError = !;
@@ -69,6 +121,10 @@ impl Primitive for Sha256Digest {
     }
 }
 
+impl_commit! {
+    Sha256Digest,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -92,4 +148,48 @@ mod test {
             hex!("7f83b1657ff1fc53b92dc18148a1d65dfc2d4b1fa3d677284addd200126d9069")
         );
     }
+
+    #[test]
+    fn commit() {
+        use hoard::blob::Blob;
+        use crate::commit::{Commit, HashCommit};
+
+        let mut hasher = Sha256Hasher::default();
+        hasher.hash_bytes(b"Hello World!");
+        let digest = hasher.finish();
+
+        assert_eq!(digest.to_commitment(), digest);
+        assert_eq!(digest.to_commitment().to_blob_bytes(), digest.as_ref());
+        assert_eq!(HashCommit::<Sha256Digest>::new(&digest).digest(), digest);
+    }
+
+    #[test]
+    fn from_bytes() {
+        let raw = hex!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        let digest = Sha256Digest::from(raw);
+
+        assert_eq!(digest, Sha256Hasher::default().finish());
+        assert_eq!(digest.as_bytes(), &raw);
+        assert_eq!(digest.into_bytes(), raw);
+        assert_eq!(<[u8; 32]>::from(digest), raw);
+    }
+
+    #[test]
+    fn sha256d_vector() {
+        assert_eq!(sha256d(b"hello").0,
+            hex!("9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50")
+        );
+    }
+
+    #[test]
+    fn tagged_hash_vectors() {
+        assert_eq!(tagged_hash("BIP0340/challenge", &[]).0,
+            hex!("c216d352f5818b7b4beacd4ae0a26fe888080823d2a598856661bcd54f1b3713")
+        );
+
+        // Leaf hash of an OP_TRUE tapscript, leaf version 0xc0.
+        assert_eq!(tagged_hash("TapLeaf", &[0xc0, 0x01, 0x51]).0,
+            hex!("a85b2107f791b26a84e7586c28cec7cb61202ed3d01944d832500f363782d675")
+        );
+    }
 }
@@ -0,0 +1,76 @@
+//! Values paired with the commitments they're claimed to have.
+
+use std::fmt;
+
+use crate::commit::{
+    Commit,
+    HashCommit,
+    Digest,
+    Sha256Digest,
+};
+use crate::validate::{ValidateError, Validator};
+
+/// A value, along with the digest it's claimed to commit to.
+///
+/// The claim can either be trusted, or validated by a `Validator`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fact<T, D: Digest = Sha256Digest> {
+    value: T,
+    digest: D,
+}
+
+impl<T: Commit, D: Digest> Fact<T, D> {
+    /// Creates a new `Fact`, calculating the digest from the value.
+    pub fn new(value: T) -> Self {
+        let digest = HashCommit::new(&value).digest();
+        Self { value, digest }
+    }
+}
+
+impl<T, D: Digest> Fact<T, D> {
+    /// Creates a new `Fact` from a value and a digest, without checking that they match.
+    ///
+    /// This function is *not* marked unsafe, as digests aren't related to memory safety.
+    pub fn new_unchecked(value: T, digest: D) -> Self {
+        Self { value, digest }
+    }
+
+    pub fn digest(&self) -> D {
+        self.digest
+    }
+
+    /// Assumes the fact is valid, returning the value.
+    pub fn trust(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Commit, D: Digest + fmt::Debug> Fact<T, D> {
+    /// Validates the fact in `ctx`, returning the value if it commits to the digest.
+    pub fn validate(self, ctx: &impl Validator) -> Result<T, ValidateError<D>> {
+        ctx.validate_commitment(&self.value, self.digest)?;
+        Ok(self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::validate::HashValidator;
+
+    #[test]
+    fn validate() {
+        let fact = Fact::<u64>::new(42);
+        assert_eq!(fact.digest(), HashCommit::new(&42u64).digest());
+        assert_eq!(fact.validate(&HashValidator), Ok(42));
+
+        let forged = Fact::<u64>::new_unchecked(43, fact.digest());
+        assert_eq!(forged.trust(), 43);
+        assert_eq!(forged.validate(&HashValidator),
+                   Err(ValidateError::Commitment {
+                       expected: fact.digest(),
+                       actual: HashCommit::new(&43u64).digest(),
+                   }));
+    }
+}
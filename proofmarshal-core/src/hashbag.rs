@@ -87,6 +87,14 @@ where T: CommitRef,
     pub fn try_target_hash_commit(&self) -> Option<HashCommit<T::CommitmentDyn, D>> {
         self.digest.get().map(HashCommit::from_digest)
     }
+
+    /// Returns the digest of the target, without loading it.
+    ///
+    /// The digest is cached; if it is missing the target is hashed, which requires the bag to be
+    /// dirty.
+    pub fn digest(&self) -> D {
+        self.target_hash_commit().digest()
+    }
 }
 
 impl<T: ?Sized + Pointee, P: Ptr, D: Digest> Deref for HashBag<T, P, D> {
@@ -209,4 +217,14 @@ mod test {
         assert_eq!(bag.target_hash_commit(),
                    HashCommit::new(&42u8));
     }
+
+    #[test]
+    fn digest() {
+        let bag = HashBag::<u8, Heap>::new(42u8);
+        assert_eq!(bag.try_target_hash_commit(), None);
+
+        let expected = HashCommit::<u8>::new(&bag.get().to_commitment());
+        assert_eq!(bag.digest(), expected.digest());
+        assert_eq!(bag.try_target_hash_commit(), Some(expected));
+    }
 }
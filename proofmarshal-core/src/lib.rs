@@ -13,8 +13,12 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
 
+pub mod bitcoin;
 pub mod commit;
+pub mod fact;
 pub mod hashbag;
+pub mod seal;
+pub mod validate;
 
 pub mod collections;
 
@@ -0,0 +1,49 @@
+//! Sealed values, for commit-reveal schemes.
+
+use crate::commit::{
+    Commit,
+    HashCommit,
+    Digest,
+    Sha256Digest,
+};
+
+/// A value that can only be unsealed by presenting the digest it commits to.
+#[derive(Debug)]
+pub struct Seal<T, D: Digest = Sha256Digest> {
+    value: T,
+    digest: D,
+}
+
+impl<T: Commit, D: Digest> Seal<T, D> {
+    /// Seals a value, returning the seal and the digest needed to unseal it.
+    pub fn seal(value: T) -> (Self, D) {
+        let digest = HashCommit::new(&value).digest();
+        (Self { value, digest }, digest)
+    }
+}
+
+impl<T, D: Digest> Seal<T, D> {
+    /// Unseals the value, returning the seal unchanged if `digest` doesn't match.
+    pub fn unseal(self, digest: D) -> Result<T, Self> {
+        if digest == self.digest {
+            Ok(self.value)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unseal() {
+        let (seal, digest) = Seal::<u64>::seal(42);
+        let (_, wrong_digest) = Seal::<u64>::seal(43);
+        assert_ne!(digest, wrong_digest);
+
+        let seal = seal.unseal(wrong_digest).unwrap_err();
+        assert_eq!(seal.unseal(digest).unwrap(), 42);
+    }
+}
@@ -0,0 +1,51 @@
+//! Validation of facts.
+
+use std::fmt;
+
+use thiserror::Error;
+
+use crate::commit::{
+    Commit,
+    HashCommit,
+    Digest,
+};
+
+/// Context in which facts are validated.
+///
+/// The validator decides how a fact's evidence is checked: `HashValidator` recalculates every
+/// commitment, while other implementations could, for example, skip commitments they've already
+/// checked.
+pub trait Validator {
+    /// Checks that `value` commits to `digest`.
+    fn validate_commitment<T, D>(&self, value: &T, digest: D) -> Result<(), ValidateError<D>>
+        where T: Commit,
+              D: Digest + fmt::Debug;
+}
+
+/// Validator that recalculates every commitment from its value.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HashValidator;
+
+impl Validator for HashValidator {
+    fn validate_commitment<T, D>(&self, value: &T, digest: D) -> Result<(), ValidateError<D>>
+        where T: Commit,
+              D: Digest + fmt::Debug,
+    {
+        let actual = HashCommit::new(value).digest();
+        if actual == digest {
+            Ok(())
+        } else {
+            Err(ValidateError::Commitment { expected: digest, actual })
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("FIXME")]
+pub enum ValidateError<D: fmt::Debug> {
+    /// The value doesn't commit to the expected digest.
+    Commitment {
+        expected: D,
+        actual: D,
+    },
+}